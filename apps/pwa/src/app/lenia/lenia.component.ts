@@ -1,8 +1,7 @@
 import { Component, OnInit } from '@angular/core';
 import { CommonModule } from '@angular/common';
-import { lenia } from '@ml/lenia';
+import { Statistics, lenia } from '@ml/lenia';
 import { HeatmapComponent } from '../heatmap/heatmap.component';
-import { memory } from '@ml/lenia/lenia_bg.wasm';
 import { MatTabsModule } from '@angular/material/tabs';
 
 @Component({
@@ -15,15 +14,25 @@ import { MatTabsModule } from '@angular/material/tabs';
 export class LeniaComponent implements OnInit {
   lenia = lenia();
   data!: number[];
+  statistics?: Statistics;
 
   ngOnInit(): void {
-    const pointer = this.lenia.state();
-    this.data = Array.from(new Float64Array(memory.buffer, pointer, 64 * 64));
+    this.data = Array.from(this.lenia.state_array());
+    this.statistics = this.lenia.statistics();
   }
 
   run() {
     this.lenia.evolve();
-    const pointer = this.lenia.state();
-    this.data = Array.from(new Float64Array(memory.buffer, pointer, 64 * 64));
+    this.data = Array.from(this.lenia.state_array());
+    this.statistics?.free();
+    this.statistics = this.lenia.statistics();
+  }
+
+  back() {
+    if (this.lenia.step_back()) {
+      this.data = Array.from(this.lenia.state_array());
+      this.statistics?.free();
+      this.statistics = this.lenia.statistics();
+    }
   }
 }
@@ -32,6 +32,8 @@ export class LifeGameControlFormComponent {
   @Output()
   playModeChange = new EventEmitter<LifeGamePlayMode>();
   @Output()
+  previous = new EventEmitter<void>();
+  @Output()
   next = new EventEmitter<void>();
 
   playModeControl = new FormControl<LifeGamePlayMode>(this.defaultPlayMode, {
@@ -6,8 +6,7 @@ import {
   ViewChild,
 } from '@angular/core';
 import { CommonModule } from '@angular/common';
-import { Cell, Universe } from '@ml/life_game';
-import { memory } from '@ml/life_game/life_game_bg.wasm';
+import { Cell, PatternKind, Universe } from '@ml/life_game';
 import { LifeGameControlFormComponent } from '../life-game-control-form/life-game-control-form.component';
 import { LifeGamePlayMode } from '../life-game.types';
 
@@ -37,6 +36,7 @@ export class LifeGameEngineComponent implements AfterViewInit {
   animationFrameId?: number;
   lastFrameTimestamp?: number;
   fps?: number;
+  periodicityLabel?: string;
 
   universe!: Universe;
 
@@ -90,6 +90,16 @@ export class LifeGameEngineComponent implements AfterViewInit {
     this.animationFrameId = requestAnimationFrame(() => {});
   }
 
+  /**
+   * Go back to the previous generation of the universe, if it
+   * is still in the history, and render it
+   */
+  previous() {
+    if (this.universe.step_back()) {
+      this.renderUniverse(this.universe, this.canvasContext);
+    }
+  }
+
   /**
    * Set the game to the updated play mode
    *
@@ -178,6 +188,37 @@ export class LifeGameEngineComponent implements AfterViewInit {
     // Rendering
     this.renderGrid(universe, canvasContext);
     this.renderCells(universe, canvasContext);
+
+    this.periodicityLabel = this.getPeriodicityLabel(universe);
+  }
+
+  /**
+   * Describe the periodic behaviour detected in the universe, if any
+   *
+   * @param universe - the universe to describe
+   * @returns a human readable label, or undefined if no period was detected
+   */
+  getPeriodicityLabel(universe: Universe) {
+    const periodicity = universe.periodicity();
+
+    if (!periodicity) return undefined;
+
+    let label: string;
+    switch (periodicity.kind) {
+      case PatternKind.StillLife:
+        label = 'Still life';
+        break;
+      case PatternKind.Oscillator:
+        label = `Oscillator (period ${periodicity.period})`;
+        break;
+      case PatternKind.Spaceship:
+        label = `Spaceship (period ${periodicity.period}, displacement ${periodicity.dx}, ${periodicity.dy})`;
+        break;
+    }
+
+    periodicity.free();
+
+    return label;
   }
 
   renderGrid(universe: Universe, canvasContext: CanvasRenderingContext2D) {
@@ -209,35 +250,26 @@ export class LifeGameEngineComponent implements AfterViewInit {
   }
 
   renderCells(universe: Universe, canvasContext: CanvasRenderingContext2D) {
-    const cellsPtr = universe.cells();
-    const cells = new Uint8Array(
-      memory.buffer,
-      cellsPtr,
-      this.width * this.height
-    );
+    const cells = universe.cells_array();
 
     canvasContext.beginPath();
 
-    for (let row = 0; row < this.height; row++) {
-      for (let col = 0; col < this.width; col++) {
-        const idx = this.getIndex(row, col);
+    // Only repaint the cells changed by the last update
+    for (const idx of universe.changed_cells()) {
+      const row = Math.floor(idx / this.width);
+      const col = idx % this.width;
 
-        canvasContext.fillStyle =
-          cells[idx] === Cell.Dead ? DEAD_COLOR : ALIVE_COLOR;
+      canvasContext.fillStyle =
+        cells[idx] === Cell.Dead ? DEAD_COLOR : ALIVE_COLOR;
 
-        canvasContext.fillRect(
-          col * (CELL_SIZE + 1) + 1,
-          row * (CELL_SIZE + 1) + 1,
-          CELL_SIZE,
-          CELL_SIZE
-        );
-      }
+      canvasContext.fillRect(
+        col * (CELL_SIZE + 1) + 1,
+        row * (CELL_SIZE + 1) + 1,
+        CELL_SIZE,
+        CELL_SIZE
+      );
     }
 
     canvasContext.stroke();
   }
-
-  getIndex(row: number, column: number) {
-    return row * this.width + column;
-  }
 }
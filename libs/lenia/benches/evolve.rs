@@ -0,0 +1,41 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use lenia::Lenia;
+use maths::{convolution::gaussian_kernel, function::gauss};
+
+fn bench_evolve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("evolve");
+    group.sample_size(10);
+
+    [64, 128, 256].iter().for_each(|&size| {
+        [5, 13, 20].iter().for_each(|&radius| {
+            let mut world = Lenia::new(
+                size,
+                10.0,
+                |x| gauss(*x, 2.0, 0.15, 0.015) - 1.0,
+                gaussian_kernel(radius, 0.5, 0.15),
+            );
+            // A soup covering most of the world
+            world.randomize(1, size as f64 / 2.0);
+            // Only the evolution is measured, not the snapshots kept for `step_back`
+            world.set_history_budget(0);
+
+            group.throughput(Throughput::Elements((size * size) as u64));
+            group.bench_function(
+                BenchmarkId::new(format!("radius {}", radius), format!("{}²", size)),
+                // Every iteration starts again from the same soup
+                |b| {
+                    b.iter_batched(
+                        || world.clone(),
+                        |mut world| world.evolve(),
+                        BatchSize::LargeInput,
+                    )
+                },
+            );
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_evolve);
+criterion_main!(benches);
@@ -0,0 +1,37 @@
+use wasm_bindgen::prelude::*;
+
+/// Points of a curve, to be plotted next to the simulation
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Curve {
+    x: Vec<f64>,
+    y: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl Curve {
+    /// Abscissas of the points
+    pub fn x(&self) -> Vec<f64> {
+        self.x.clone()
+    }
+
+    /// Ordinates of the points
+    pub fn y(&self) -> Vec<f64> {
+        self.y.clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+}
+
+impl From<Vec<(f64, f64)>> for Curve {
+    fn from(points: Vec<(f64, f64)>) -> Self {
+        let (x, y) = points.into_iter().unzip();
+        Self { x, y }
+    }
+}
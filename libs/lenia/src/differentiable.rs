@@ -0,0 +1,404 @@
+use maths::{
+    convolution,
+    coordinate::Coordinate,
+    function::{exponential_bump, normal_gauss},
+    matrix::Matrix,
+    statistics::periodic_difference,
+};
+
+/// Parameters of a Lenia world that can be learnt
+///
+/// The kernel is made of concentric rings of equal widths, each one being
+/// an exponential bump (see `maths::function::exponential_bump`) weighted
+/// by its ring weight. The growth function is
+/// `2·exp(-(u - mu)² / 2·sigma²) - 1`, and a step of the world is the
+/// Euler update `clamp(A + G(K*A) / time_constant, 0, 1)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameters {
+    /// Radius of the kernel (in cells), it is not learnt
+    pub radius: usize,
+    pub ring_weights: Vec<f64>,
+    pub mu: f64,
+    pub sigma: f64,
+    pub time_constant: f64,
+}
+
+impl Parameters {
+    /// Number of learnt parameters
+    pub fn count(&self) -> usize {
+        self.ring_weights.len() + 3
+    }
+
+    /// Learnt parameters, in the order: ring weights, mu, sigma, time constant
+    pub fn to_vec(&self) -> Vec<f64> {
+        let mut values = self.ring_weights.clone();
+        values.extend([self.mu, self.sigma, self.time_constant]);
+        values
+    }
+
+    /// Instantiate parameters with the same radius, from learnt
+    /// parameters in the order of `to_vec`
+    pub fn with_values(&self, values: &[f64]) -> Self {
+        let rings = self.ring_weights.len();
+
+        Self {
+            radius: self.radius,
+            ring_weights: values[..rings].to_vec(),
+            mu: values[rings],
+            sigma: values[rings + 1],
+            time_constant: values[rings + 2],
+        }
+    }
+
+    /// Denote if the parameters describe a valid world
+    pub fn is_valid(&self) -> bool {
+        self.radius > 0
+            && self.mu.is_finite()
+            && self.sigma > 0.0
+            && self.time_constant > 0.0
+            && self.ring_weights.iter().all(|&weight| weight >= 0.0)
+            && self.ring_weights.iter().any(|&weight| weight > 0.0)
+    }
+
+    /// Normalized kernel, and its derivatives with respect to each ring weight
+    pub fn kernel(&self) -> (Matrix<f64>, Vec<Matrix<f64>>) {
+        let rings = self.ring_weights.len();
+        let diameter = 2 * self.radius + 1;
+
+        // Unweighted bumps of each ring
+        let bumps: Vec<Matrix<f64>> = (0..rings)
+            .map(|ring| {
+                Matrix::from_function(diameter, diameter, |x, y| {
+                    let delta_x = x as f64 - self.radius as f64;
+                    let delta_y = y as f64 - self.radius as f64;
+                    let distance =
+                        (delta_x * delta_x + delta_y * delta_y).sqrt() / self.radius as f64;
+                    let position = distance * rings as f64;
+
+                    if distance < 1.0 && position.floor() as usize == ring {
+                        exponential_bump(position.fract())
+                    } else {
+                        0.0
+                    }
+                })
+            })
+            .collect();
+
+        let shell = bumps.iter().zip(&self.ring_weights).fold(
+            Matrix::from_constant(diameter, diameter, 0.0),
+            |shell, (bump, weight)| shell.add_scaled(bump, *weight),
+        );
+        let total: f64 = shell.m.iter().sum();
+
+        // K = S / Z, so dK/dβ = C / Z - S·sum(C) / Z²
+        let derivatives = bumps
+            .iter()
+            .map(|bump| {
+                let bump_total: f64 = bump.m.iter().sum();
+                bump.scale(1.0 / total)
+                    .add_scaled(&shell, -bump_total / (total * total))
+            })
+            .collect();
+
+        (shell.scale(1.0 / total), derivatives)
+    }
+}
+
+/// Compute a step of the world
+pub fn step(parameters: &Parameters, kernel: &Matrix<f64>, state: &Matrix<f64>) -> Matrix<f64> {
+    let potential = convolve(state, kernel);
+
+    Matrix::from_function(state.width, state.height, |x, y| {
+        let point = Coordinate(x, y);
+        let growth = growth(parameters, *potential.get_by_coordinate(&point)).0;
+
+        (state.get_by_coordinate(&point) + growth / parameters.time_constant).clamp(0.0, 1.0)
+    })
+}
+
+/// Compute a step of the world, and of its derivatives with respect
+/// to each learnt parameter (in the order of `Parameters::to_vec`)
+///
+/// `kernel_derivatives` are the derivatives of the kernel with respect to
+/// the ring weights. The derivative of a clamped cell is 0.
+pub fn step_with_tangents(
+    parameters: &Parameters,
+    kernel: &Matrix<f64>,
+    kernel_derivatives: &[Matrix<f64>],
+    state: &Matrix<f64>,
+    tangents: &[Matrix<f64>],
+) -> (Matrix<f64>, Vec<Matrix<f64>>) {
+    let rings = parameters.ring_weights.len();
+    let time_constant = parameters.time_constant;
+    let potential = convolve(state, kernel);
+
+    let mut next_state = state.clone();
+    let mut next_tangents: Vec<Matrix<f64>> = tangents
+        .iter()
+        .enumerate()
+        .map(|(parameter, tangent)| {
+            // The potential depends on the parameters through the state,
+            // and directly through the kernel for the ring weights
+            let mut potential_tangent = convolve(tangent, kernel);
+            if parameter < rings {
+                potential_tangent = potential_tangent
+                    .add_scaled(&convolve(state, &kernel_derivatives[parameter]), 1.0);
+            }
+            potential_tangent
+        })
+        .collect();
+
+    for index in 0..state.m.len() {
+        let (growth, d_potential, d_mu, d_sigma) = growth(parameters, potential.m[index]);
+        let unclamped = state.m[index] + growth / time_constant;
+        let is_clamped = !(0.0..=1.0).contains(&unclamped);
+        next_state.m[index] = unclamped.clamp(0.0, 1.0);
+
+        next_tangents.iter_mut().zip(tangents).enumerate().for_each(
+            |(parameter, (next_tangent, tangent))| {
+                let mut d_growth = d_potential * next_tangent.m[index];
+                if parameter == rings {
+                    d_growth += d_mu;
+                } else if parameter == rings + 1 {
+                    d_growth += d_sigma;
+                }
+
+                let mut derivative = tangent.m[index] + d_growth / time_constant;
+                if parameter == rings + 2 {
+                    derivative -= growth / (time_constant * time_constant);
+                }
+
+                next_tangent.m[index] = if is_clamped { 0.0 } else { derivative };
+            },
+        );
+    }
+
+    (next_state, next_tangents)
+}
+
+/// Compute the state of the world after the specified number of steps
+pub fn simulate(parameters: &Parameters, initial_state: &Matrix<f64>, steps: usize) -> Matrix<f64> {
+    let (kernel, _) = parameters.kernel();
+
+    (0..steps).fold(initial_state.clone(), |state, _| {
+        step(parameters, &kernel, &state)
+    })
+}
+
+/// Compute the value of a loss after the specified number of steps, and
+/// its gradient with respect to the learnt parameters (in the order of
+/// `Parameters::to_vec`)
+///
+/// The loss returns its value for a state, and its derivative with
+/// respect to each cell of the state. Gradients are computed in forward
+/// mode: the derivatives of the state are carried along the steps.
+pub fn gradient<L>(
+    parameters: &Parameters,
+    initial_state: &Matrix<f64>,
+    steps: usize,
+    loss: L,
+) -> (f64, Vec<f64>)
+where
+    L: Fn(&Matrix<f64>) -> (f64, Matrix<f64>),
+{
+    let (kernel, kernel_derivatives) = parameters.kernel();
+    let zero = Matrix::from_constant(initial_state.width, initial_state.height, 0.0);
+
+    let (state, tangents) = (0..steps).fold(
+        (initial_state.clone(), vec![zero; parameters.count()]),
+        |(state, tangents), _| {
+            step_with_tangents(parameters, &kernel, &kernel_derivatives, &state, &tangents)
+        },
+    );
+
+    let (value, loss_derivative) = loss(&state);
+    let gradient = tangents
+        .iter()
+        .map(|tangent| {
+            tangent
+                .m
+                .iter()
+                .zip(&loss_derivative.m)
+                .map(|(a, b)| a * b)
+                .sum()
+        })
+        .collect();
+
+    (value, gradient)
+}
+
+/// Loss rewarding the mass close to a target position: the opposite of the
+/// mass weighted by a (toroidal) gaussian window of the specified radius
+pub fn target_mass_loss(
+    state: &Matrix<f64>,
+    target: (f64, f64),
+    radius: f64,
+) -> (f64, Matrix<f64>) {
+    let window = Matrix::from_function(state.width, state.height, |x, y| {
+        let delta_x = periodic_difference(target.0, x as f64, state.width as f64);
+        let delta_y = periodic_difference(target.1, y as f64, state.height as f64);
+        normal_gauss((delta_x * delta_x + delta_y * delta_y).sqrt(), 0.0, radius)
+    });
+
+    let value = -state
+        .m
+        .iter()
+        .zip(&window.m)
+        .map(|(value, weight)| value * weight)
+        .sum::<f64>();
+
+    (value, window.scale(-1.0))
+}
+
+/// Minimize a loss by gradient descent on the learnt parameters
+///
+/// When a step does not decrease the loss, or leads to invalid parameters,
+/// the learning rate is halved and the step is tried again. Return the
+/// best parameters and the loss after each iteration.
+pub fn gradient_descent<L>(
+    parameters: &Parameters,
+    initial_state: &Matrix<f64>,
+    steps: usize,
+    loss: L,
+    mut learning_rate: f64,
+    iterations: usize,
+) -> (Parameters, Vec<f64>)
+where
+    L: Fn(&Matrix<f64>) -> (f64, Matrix<f64>),
+{
+    let mut parameters = parameters.clone();
+    let mut losses = Vec::with_capacity(iterations);
+
+    for _ in 0..iterations {
+        let (value, gradient) = gradient(&parameters, initial_state, steps, &loss);
+
+        // Backtracking until the loss decreases
+        for _ in 0..32 {
+            let values: Vec<f64> = parameters
+                .to_vec()
+                .iter()
+                .zip(&gradient)
+                .map(|(value, gradient)| value - learning_rate * gradient)
+                .collect();
+            let candidate = parameters.with_values(&values);
+
+            if candidate.is_valid() && loss(&simulate(&candidate, initial_state, steps)).0 < value {
+                parameters = candidate;
+                break;
+            }
+            learning_rate /= 2.0;
+        }
+
+        losses.push(loss(&simulate(&parameters, initial_state, steps)).0);
+    }
+
+    (parameters, losses)
+}
+
+/// Growth of a potential, and its derivatives with respect
+/// to the potential, mu and sigma
+fn growth(parameters: &Parameters, potential: f64) -> (f64, f64, f64, f64) {
+    let Parameters { mu, sigma, .. } = *parameters;
+    let delta = potential - mu;
+    let bump = 2.0 * (-delta * delta / (2.0 * sigma * sigma)).exp();
+
+    (
+        bump - 1.0,
+        -bump * delta / (sigma * sigma),
+        bump * delta / (sigma * sigma),
+        bump * delta * delta / (sigma * sigma * sigma),
+    )
+}
+
+/// Toroidal convolution of a matrix by a kernel
+fn convolve(matrix: &Matrix<f64>, kernel: &Matrix<f64>) -> Matrix<f64> {
+    Matrix::from_function(matrix.width, matrix.height, |x, y| {
+        convolution::convolve(&Coordinate(x, y), matrix, kernel)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::soup::uniform_soup;
+    use maths::random::Random;
+
+    fn parameters() -> Parameters {
+        Parameters {
+            radius: 4,
+            ring_weights: vec![1.0, 0.5],
+            mu: 0.2,
+            sigma: 0.05,
+            time_constant: 10.0,
+        }
+    }
+
+    fn initial_state() -> Matrix<f64> {
+        uniform_soup(16, 5.0, &mut Random::new(1))
+    }
+
+    #[test]
+    fn test_kernel() {
+        let (kernel, derivatives) = parameters().kernel();
+
+        assert_eq!(kernel.width, 9);
+        assert!((kernel.m.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(*kernel.get_by_coordinate(&Coordinate(0, 0)), 0.0);
+        // The kernel stays normalized whatever the weights
+        derivatives
+            .iter()
+            .for_each(|derivative| assert!(derivative.m.iter().sum::<f64>().abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_gradient() {
+        // Forward mode gradients match finite differences
+        let parameters = parameters();
+        let initial_state = initial_state();
+        let loss = |state: &Matrix<f64>| target_mass_loss(state, (10.0, 8.0), 3.0);
+        let epsilon = 1e-6;
+
+        let (value, gradient) = super::gradient(&parameters, &initial_state, 3, loss);
+        assert_eq!(value, loss(&simulate(&parameters, &initial_state, 3)).0);
+
+        let values = parameters.to_vec();
+        (0..parameters.count()).for_each(|index| {
+            let shifted = |shift: f64| {
+                let mut values = values.clone();
+                values[index] += shift;
+                loss(&simulate(
+                    &parameters.with_values(&values),
+                    &initial_state,
+                    3,
+                ))
+                .0
+            };
+            let finite_difference = (shifted(epsilon) - shifted(-epsilon)) / (2.0 * epsilon);
+
+            assert!(
+                (gradient[index] - finite_difference).abs()
+                    < 1e-5 * finite_difference.abs().max(1.0),
+                "parameter {}: {} != {}",
+                index,
+                gradient[index],
+                finite_difference
+            );
+        });
+    }
+
+    #[test]
+    fn test_gradient_descent() {
+        let parameters = parameters();
+        let initial_state = initial_state();
+        let loss = |state: &Matrix<f64>| target_mass_loss(state, (10.0, 8.0), 3.0);
+        let initial_loss = loss(&simulate(&parameters, &initial_state, 3)).0;
+
+        let (learnt, losses) = gradient_descent(&parameters, &initial_state, 3, loss, 1e-3, 4);
+
+        assert_eq!(losses.len(), 4);
+        assert!(losses[0] < initial_loss);
+        assert!(losses.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert!(learnt.is_valid());
+        assert_eq!(learnt.radius, parameters.radius);
+    }
+}
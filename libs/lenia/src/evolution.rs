@@ -0,0 +1,862 @@
+use maths::{
+    matrix::Matrix,
+    random::Random,
+    statistics::{periodic_difference, toroidal_centroid, toroidal_variance},
+};
+use serde_json::{json, Value};
+
+use crate::differentiable::{self, Parameters};
+
+/// Mass under which a creature is considered dead
+const EXTINCTION_MASS: f64 = 1e-3;
+
+/// Part of the world that a creature can cover before it is
+/// considered as exploded
+const EXPLOSION_COVERAGE: f64 = 0.5;
+
+/// Number of levels of a cell value in the run length encoding of the cells
+const LEVELS: f64 = 255.0;
+
+/// Largest width and height of a decoded patch
+const MAX_PATCH_SIZE: usize = 1024;
+
+/// Candidate creature: the parameters of a world, and the patch
+/// of cells placed at the center of the world to start it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Genome {
+    pub parameters: Parameters,
+    pub patch: Matrix<f64>,
+}
+
+impl Genome {
+    /// Generate a random genome with the specified kernel shape and patch size
+    pub fn random(radius: usize, rings: usize, patch_size: usize, random: &mut Random) -> Self {
+        let parameters = Parameters {
+            radius,
+            // The first ring is never empty, so that the kernel is valid
+            ring_weights: (0..rings)
+                .map(|ring| {
+                    let weight = random.next_f64();
+                    if ring == 0 {
+                        0.1 + 0.9 * weight
+                    } else {
+                        weight
+                    }
+                })
+                .collect(),
+            mu: 0.1 + 0.3 * random.next_f64(),
+            sigma: 0.005 + 0.045 * random.next_f64(),
+            time_constant: 5.0 + 15.0 * random.next_f64(),
+        };
+        let patch = Matrix::from_function(patch_size, patch_size, |_, _| random.next_f64());
+
+        Self { parameters, patch }
+    }
+
+    /// Copy the genome, perturbing each parameter and each cell of the
+    /// patch with the probability `rate`
+    ///
+    /// A perturbation is a uniform random change of at most `strength`,
+    /// relatively to the range of the value. If the perturbed parameters
+    /// are invalid, they are discarded and only the patch is perturbed.
+    pub fn mutate(&self, rate: f64, strength: f64, random: &mut Random) -> Self {
+        let mut perturb = |value: f64, range: f64| {
+            if random.next_bool(rate) {
+                value + strength * range * (2.0 * random.next_f64() - 1.0)
+            } else {
+                value
+            }
+        };
+
+        let ring_weights: Vec<f64> = self
+            .parameters
+            .ring_weights
+            .iter()
+            .map(|&weight| perturb(weight, 1.0).clamp(0.0, 1.0))
+            .collect();
+        let parameters = Parameters {
+            radius: self.parameters.radius,
+            ring_weights,
+            mu: perturb(self.parameters.mu, 0.5).clamp(0.0, 1.0),
+            sigma: perturb(self.parameters.sigma, 0.05),
+            time_constant: perturb(self.parameters.time_constant, 20.0),
+        };
+        let mut patch = self.patch.clone();
+        patch
+            .iter_mut()
+            .for_each(|cell| *cell = perturb(*cell, 1.0).clamp(0.0, 1.0));
+
+        Self {
+            parameters: if parameters.is_valid() {
+                parameters
+            } else {
+                self.parameters.clone()
+            },
+            patch,
+        }
+    }
+
+    /// Mix two genomes: each parameter comes from one of the parents, and
+    /// the patch rows come from the first parent above a random row,
+    /// from the second one below
+    ///
+    /// Both genomes must have the same kernel shape and patch size.
+    pub fn crossover(&self, other: &Genome, random: &mut Random) -> Self {
+        assert_eq!(self.parameters.radius, other.parameters.radius);
+        assert_eq!(self.parameters.count(), other.parameters.count());
+        assert_eq!(
+            (self.patch.width, self.patch.height),
+            (other.patch.width, other.patch.height)
+        );
+
+        let values: Vec<f64> = self
+            .parameters
+            .to_vec()
+            .into_iter()
+            .zip(other.parameters.to_vec())
+            .map(|(a, b)| if random.next_bool(0.5) { a } else { b })
+            .collect();
+
+        let split = random.next_below(self.patch.height + 1) * self.patch.width;
+        let mut patch = self.patch.clone();
+        patch.m[split..].copy_from_slice(&other.patch.m[split..]);
+
+        Self {
+            parameters: self.parameters.with_values(&values),
+            patch,
+        }
+    }
+
+    /// Square world of the specified size, with the patch at its center
+    ///
+    /// A patch larger than the world is cropped around its center.
+    pub fn world(&self, size: usize) -> Matrix<f64> {
+        let width = self.patch.width as isize;
+        let height = self.patch.height as isize;
+        let left = (size as isize - width) / 2;
+        let top = (size as isize - height) / 2;
+
+        Matrix::from_function(size, size, |x, y| {
+            let patch_x = x as isize - left;
+            let patch_y = y as isize - top;
+
+            if (0..width).contains(&patch_x) && (0..height).contains(&patch_y) {
+                self.patch.m[(patch_y * width + patch_x) as usize]
+            } else {
+                0.0
+            }
+        })
+    }
+
+    /// Describe the genome in the Lenia JSON format
+    ///
+    /// The growth is the gaussian one (`gn` = 2), and the kernel rings are
+    /// the exponential bumps of `Parameters::kernel` (`kn` = 2). Cells are
+    /// run length encoded on 255 levels.
+    pub fn to_json(&self, code: &str, name: &str) -> Value {
+        let ring_weights: Vec<String> = self
+            .parameters
+            .ring_weights
+            .iter()
+            .map(|weight| weight.to_string())
+            .collect();
+
+        json!({
+            "code": code,
+            "name": name,
+            "params": {
+                "R": self.parameters.radius,
+                "T": self.parameters.time_constant,
+                "b": ring_weights.join(","),
+                "m": self.parameters.mu,
+                "s": self.parameters.sigma,
+                "kn": 2,
+                "gn": 2,
+            },
+            "cells": encode_cells(&self.patch),
+        })
+    }
+
+    /// Read a genome described in the Lenia JSON format
+    ///
+    /// Only the kernel core and the growth of `to_json` are supported.
+    pub fn from_json(creature: &Value) -> Result<Self, String> {
+        let params = &creature["params"];
+        let number = |key: &str| {
+            params[key]
+                .as_f64()
+                .ok_or_else(|| format!("Missing parameter: {}", key))
+        };
+
+        for key in ["kn", "gn"] {
+            if !params[key].is_null() && params[key].as_u64() != Some(2) {
+                return Err(format!("Unsupported parameter: {} = {}", key, params[key]));
+            }
+        }
+
+        let ring_weights = params["b"]
+            .as_str()
+            .ok_or("Missing parameter: b")?
+            .split(',')
+            .map(parse_fraction)
+            .collect::<Result<Vec<f64>, String>>()?;
+        let cells = creature["cells"].as_str().ok_or("Missing cells")?;
+
+        let parameters = Parameters {
+            radius: number("R")? as usize,
+            ring_weights,
+            mu: number("m")?,
+            sigma: number("s")?,
+            time_constant: number("T")?,
+        };
+        if !parameters.is_valid() {
+            return Err(format!("Invalid parameters: {:?}", parameters));
+        }
+
+        Ok(Self {
+            parameters,
+            patch: decode_cells(cells)?,
+        })
+    }
+}
+
+/// Measures of a world along a simulation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Trace {
+    /// Number of steps before the creature died or exploded
+    pub survival: usize,
+    /// Mass of the world after each step it survived
+    pub masses: Vec<f64>,
+    /// Mass weighted variance of the distance to the center
+    /// of mass, after each step it survived
+    pub spreads: Vec<f64>,
+    /// Sum of the displacements of the center of mass (in cells)
+    pub distance: f64,
+}
+
+impl Trace {
+    /// Simulate a genome in a square world of the specified size
+    ///
+    /// The simulation stops as soon as the creature dies or fills the world.
+    pub fn record(genome: &Genome, size: usize, steps: usize) -> Self {
+        let (kernel, _) = genome.parameters.kernel();
+        let mut state = genome.world(size);
+        let mut centroid = toroidal_centroid(&state);
+        let mut trace = Trace::default();
+
+        for _ in 0..steps {
+            state = differentiable::step(&genome.parameters, &kernel, &state);
+
+            let mass: f64 = state.iter().sum();
+            if mass < EXTINCTION_MASS || mass > EXPLOSION_COVERAGE * (size * size) as f64 {
+                break;
+            }
+            let Some(next_centroid) = toroidal_centroid(&state) else {
+                break;
+            };
+
+            if let Some(previous) = centroid {
+                let delta_x = periodic_difference(previous.0, next_centroid.0, size as f64);
+                let delta_y = periodic_difference(previous.1, next_centroid.1, size as f64);
+                trace.distance += delta_x.hypot(delta_y);
+            }
+            centroid = Some(next_centroid);
+
+            trace.survival += 1;
+            trace.masses.push(mass);
+            trace.spreads.push(toroidal_variance(&state, next_centroid));
+        }
+
+        trace
+    }
+}
+
+/// Criterion used to score the genomes, the higher the better
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fitness {
+    /// Number of steps survived
+    SurvivalTime,
+    /// Distance travelled by the center of mass, only
+    /// counted if the creature survived the whole simulation
+    TravelledDistance,
+    /// Inverse of the variations of the mass and the spread, in `(0, 1]`,
+    /// only counted if the creature survived the whole simulation
+    ShapeStability,
+}
+
+impl Fitness {
+    /// Score the trace of a simulation of the specified number of steps
+    pub fn score(&self, trace: &Trace, steps: usize) -> f64 {
+        match self {
+            Fitness::SurvivalTime => trace.survival as f64,
+            _ if trace.survival < steps => 0.0,
+            Fitness::TravelledDistance => trace.distance,
+            Fitness::ShapeStability => {
+                1.0 / (1.0
+                    + coefficient_of_variation(&trace.masses)
+                    + coefficient_of_variation(&trace.spreads))
+            }
+        }
+    }
+}
+
+/// Settings of an evolutionary search
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Size of the square world the genomes are simulated in
+    pub world_size: usize,
+    /// Size of the square patch of the genomes
+    pub patch_size: usize,
+    /// Radius of the kernel of the genomes
+    pub radius: usize,
+    /// Number of kernel rings of the genomes
+    pub rings: usize,
+    /// Number of steps of each simulation
+    pub steps: usize,
+    pub fitness: Fitness,
+    pub population_size: usize,
+    /// Number of best genomes kept unchanged in the next generation
+    pub elite_count: usize,
+    /// Number of genomes competing to be selected as a parent
+    pub tournament_size: usize,
+    pub mutation_rate: f64,
+    pub mutation_strength: f64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            world_size: 64,
+            patch_size: 20,
+            radius: 13,
+            rings: 1,
+            steps: 200,
+            fitness: Fitness::TravelledDistance,
+            population_size: 32,
+            elite_count: 4,
+            tournament_size: 3,
+            mutation_rate: 0.2,
+            mutation_strength: 0.1,
+        }
+    }
+}
+
+/// Genome and its score
+#[derive(Debug, Clone, PartialEq)]
+pub struct Creature {
+    pub genome: Genome,
+    pub fitness: f64,
+}
+
+/// Genetic algorithm searching for Lenia creatures
+///
+/// Every random draw comes from a single generator: the same settings
+/// and seed always lead to the same creatures.
+pub struct Evolution {
+    settings: Settings,
+    random: Random,
+    population: Vec<Genome>,
+    generation: usize,
+    archive: Vec<Creature>,
+}
+
+impl Evolution {
+    /// Instantiate a search with a random population
+    pub fn new(settings: Settings, seed: u64) -> Result<Self, String> {
+        if settings.population_size == 0 {
+            return Err("The population can not be empty".to_string());
+        }
+        // Random genomes are valid as long as their kernel is
+        if settings.radius == 0 || settings.rings == 0 {
+            return Err(format!(
+                "Invalid kernel: radius {}, {} rings",
+                settings.radius, settings.rings
+            ));
+        }
+
+        let mut random = Random::new(seed);
+        let population = (0..settings.population_size)
+            .map(|_| {
+                Genome::random(
+                    settings.radius,
+                    settings.rings,
+                    settings.patch_size,
+                    &mut random,
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            settings,
+            random,
+            population,
+            generation: 0,
+            archive: Vec::new(),
+        })
+    }
+
+    /// Score the population, archive its best creature, and replace
+    /// it by the next generation
+    ///
+    /// Return the creatures of the scored population, the best first.
+    pub fn step(&mut self) -> Vec<Creature> {
+        let Settings {
+            world_size, steps, ..
+        } = self.settings;
+
+        let mut creatures: Vec<Creature> = self
+            .population
+            .iter()
+            .map(|genome| Creature {
+                genome: genome.clone(),
+                fitness: self
+                    .settings
+                    .fitness
+                    .score(&Trace::record(genome, world_size, steps), steps),
+            })
+            .collect();
+        creatures.sort_by(|a, b| b.fitness.total_cmp(&a.fitness));
+
+        if let Some(best) = creatures.first() {
+            self.archive.push(best.clone());
+        }
+
+        let mut population: Vec<Genome> = creatures
+            .iter()
+            .take(self.settings.elite_count)
+            .map(|creature| creature.genome.clone())
+            .collect();
+        while population.len() < self.settings.population_size {
+            let first = self.select(&creatures);
+            let second = self.select(&creatures);
+            let child = first.crossover(second, &mut self.random).mutate(
+                self.settings.mutation_rate,
+                self.settings.mutation_strength,
+                &mut self.random,
+            );
+            population.push(child);
+        }
+
+        self.population = population;
+        self.generation += 1;
+
+        creatures
+    }
+
+    /// Compute the specified number of generations
+    pub fn run(&mut self, generations: usize) {
+        (0..generations).for_each(|_| {
+            self.step();
+        });
+    }
+
+    /// Number of generations computed since the search creation
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Best creature of each generation computed
+    pub fn archive(&self) -> &[Creature] {
+        &self.archive
+    }
+
+    /// Describe the best creature of each generation as a Lenia JSON array
+    ///
+    /// Creatures are named after their generation and their fitness.
+    pub fn archive_to_json(&self) -> String {
+        let creatures: Vec<Value> = self
+            .archive
+            .iter()
+            .enumerate()
+            .map(|(generation, creature)| {
+                creature.genome.to_json(
+                    &format!("E{}", generation),
+                    &format!(
+                        "Generation {} ({:?}: {})",
+                        generation, self.settings.fitness, creature.fitness
+                    ),
+                )
+            })
+            .collect();
+
+        serde_json::to_string_pretty(&creatures).unwrap()
+    }
+
+    /// Select a parent by tournament: the best of a few random creatures
+    fn select<'a>(&mut self, creatures: &'a [Creature]) -> &'a Genome {
+        let best = (0..self.settings.tournament_size.max(1))
+            .map(|_| self.random.next_below(creatures.len()))
+            .min()
+            .unwrap();
+
+        // Creatures are sorted, the smallest index is the best one
+        &creatures[best].genome
+    }
+}
+
+/// Standard deviation relatively to the mean, 0 for an empty or null series
+fn coefficient_of_variation(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    if mean == 0.0 {
+        return 0.0;
+    }
+
+    let variance = values
+        .iter()
+        .map(|value| (value - mean) * (value - mean))
+        .sum::<f64>()
+        / count;
+
+    variance.sqrt() / mean.abs()
+}
+
+/// Parse a number written as a decimal or a fraction (`2/3`)
+fn parse_fraction(text: &str) -> Result<f64, String> {
+    let parse = |number: &str| {
+        number
+            .trim()
+            .parse::<f64>()
+            .map_err(|_| format!("Invalid number: {}", text))
+    };
+
+    match text.split_once('/') {
+        Some((numerator, denominator)) => Ok(parse(numerator)? / parse(denominator)?),
+        None => parse(text),
+    }
+}
+
+/// Symbol of a cell level in the run length encoding of the cells
+fn level_to_symbol(level: u8) -> String {
+    match level {
+        0 => ".".to_string(),
+        1..=24 => char::from(b'A' + level - 1).to_string(),
+        _ => {
+            let level = level - 25;
+            format!(
+                "{}{}",
+                char::from(b'p' + level / 24),
+                char::from(b'A' + level % 24)
+            )
+        }
+    }
+}
+
+/// Run length encode the cells of a patch: rows are separated by `$`,
+/// the encoding ends with `!`
+fn encode_cells(patch: &Matrix<f64>) -> String {
+    let rows: Vec<String> = patch
+        .m
+        .chunks(patch.width)
+        .map(|row| {
+            let mut levels: Vec<u8> = row
+                .iter()
+                .map(|value| (value.clamp(0.0, 1.0) * LEVELS).round() as u8)
+                .collect();
+            // Trailing empty cells are implicit
+            while levels.last() == Some(&0) {
+                levels.pop();
+            }
+
+            let mut encoded = String::new();
+            let mut index = 0;
+            while index < levels.len() {
+                let run = levels[index..]
+                    .iter()
+                    .take_while(|&&level| level == levels[index])
+                    .count();
+                if run > 1 {
+                    encoded.push_str(&run.to_string());
+                }
+                encoded.push_str(&level_to_symbol(levels[index]));
+                index += run;
+            }
+            encoded
+        })
+        .collect();
+
+    format!("{}!", rows.join("$"))
+}
+
+/// Decode run length encoded cells (see `encode_cells`)
+///
+/// `b` and `o` are accepted as empty and full cells, as in the Life format.
+fn decode_cells(encoded: &str) -> Result<Matrix<f64>, String> {
+    let mut rows: Vec<Vec<f64>> = vec![Vec::new()];
+    let mut symbols = encoded.chars().peekable();
+    let mut run = String::new();
+
+    while let Some(symbol) = symbols.next() {
+        let count = if run.is_empty() {
+            1
+        } else {
+            run.parse::<usize>()
+                .ok()
+                .filter(|&count| count <= MAX_PATCH_SIZE)
+                .ok_or_else(|| format!("Invalid run length: {}", run))?
+        };
+
+        let level = match symbol {
+            '0'..='9' => {
+                run.push(symbol);
+                continue;
+            }
+            '!' => break,
+            '$' => {
+                if rows.len() + count > MAX_PATCH_SIZE {
+                    return Err(format!("Too many rows: {}", rows.len() + count));
+                }
+
+                (0..count).for_each(|_| rows.push(Vec::new()));
+                run.clear();
+                continue;
+            }
+            '.' | 'b' => 0,
+            'o' => 255,
+            'A'..='X' => symbol as u32 - 'A' as u32 + 1,
+            'p'..='y' => match symbols.next() {
+                Some(low @ 'A'..='X') => {
+                    (symbol as u32 - 'p' as u32) * 24 + (low as u32 - 'A' as u32) + 25
+                }
+                _ => return Err(format!("Invalid cells: {}", encoded)),
+            },
+            _ if symbol.is_whitespace() => continue,
+            _ => return Err(format!("Invalid cells: {}", encoded)),
+        };
+
+        let row = rows.last_mut().unwrap();
+        if row.len() + count > MAX_PATCH_SIZE {
+            return Err(format!("Row too long: {}", row.len() + count));
+        }
+
+        row.resize(row.len() + count, level.min(255) as f64 / LEVELS);
+        run.clear();
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let height = rows.len();
+    let cells = rows
+        .into_iter()
+        .flat_map(|mut row| {
+            row.resize(width, 0.0);
+            row
+        })
+        .collect();
+
+    Matrix::from_vec(cells, width, height)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn settings() -> Settings {
+        Settings {
+            world_size: 16,
+            patch_size: 6,
+            radius: 3,
+            rings: 2,
+            steps: 10,
+            fitness: Fitness::SurvivalTime,
+            population_size: 6,
+            elite_count: 1,
+            tournament_size: 2,
+            mutation_rate: 0.5,
+            mutation_strength: 0.2,
+        }
+    }
+
+    #[test]
+    fn test_mutation_and_crossover() {
+        let mut random = Random::new(7);
+        let first = Genome::random(3, 2, 4, &mut random);
+        let second = Genome::random(3, 2, 4, &mut random);
+
+        // Seeded operators are reproducible
+        let mutant = first.mutate(0.5, 0.2, &mut Random::new(1));
+        assert_eq!(mutant, first.mutate(0.5, 0.2, &mut Random::new(1)));
+        assert_ne!(mutant, first);
+        assert!(mutant.parameters.is_valid());
+        assert!(mutant.patch.iter().all(|cell| (0.0..=1.0).contains(cell)));
+        assert_eq!(first.mutate(0.0, 0.2, &mut Random::new(1)), first);
+
+        let child = first.crossover(&second, &mut Random::new(2));
+        assert_eq!(child, first.crossover(&second, &mut Random::new(2)));
+        child
+            .parameters
+            .to_vec()
+            .iter()
+            .zip(
+                first
+                    .parameters
+                    .to_vec()
+                    .iter()
+                    .zip(second.parameters.to_vec()),
+            )
+            .for_each(|(value, (a, b))| assert!(*value == *a || *value == b));
+        child
+            .patch
+            .iter()
+            .zip(first.patch.iter().zip(second.patch.iter()))
+            .for_each(|(cell, (a, b))| assert!(cell == a || cell == b));
+    }
+
+    #[test]
+    fn test_world() {
+        let genome = Genome {
+            parameters: Genome::random(3, 1, 2, &mut Random::new(0)).parameters,
+            patch: Matrix::from_vec(vec![0.1, 0.2, 0.3, 0.4], 2, 2).unwrap(),
+        };
+        let world = genome.world(6);
+
+        assert_eq!(world.m[2 * 6 + 2], 0.1);
+        assert_eq!(world.m[3 * 6 + 3], 0.4);
+        assert!((world.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        // A patch larger than the world is cropped
+        let cropped = genome.world(1);
+        assert_eq!(cropped.m, vec![0.1]);
+    }
+
+    #[test]
+    fn test_fitness() {
+        // An empty world dies at once
+        let mut genome = Genome::random(3, 1, 4, &mut Random::new(0));
+        genome.patch = Matrix::from_constant(4, 4, 0.0);
+        let trace = Trace::record(&genome, 16, 10);
+        assert_eq!(trace.survival, 0);
+        assert_eq!(Fitness::SurvivalTime.score(&trace, 10), 0.0);
+        assert_eq!(Fitness::TravelledDistance.score(&trace, 10), 0.0);
+        assert_eq!(Fitness::ShapeStability.score(&trace, 10), 0.0);
+
+        let stable = Trace {
+            survival: 3,
+            masses: vec![2.0; 3],
+            spreads: vec![1.0; 3],
+            distance: 1.5,
+        };
+        assert_eq!(Fitness::SurvivalTime.score(&stable, 3), 3.0);
+        assert_eq!(Fitness::TravelledDistance.score(&stable, 3), 1.5);
+        assert_eq!(Fitness::ShapeStability.score(&stable, 3), 1.0);
+
+        let unstable = Trace {
+            masses: vec![1.0, 3.0, 2.0],
+            ..stable
+        };
+        assert!(Fitness::ShapeStability.score(&unstable, 3) < 1.0);
+    }
+
+    #[test]
+    fn test_evolution() {
+        let mut evolution = Evolution::new(settings(), 11).unwrap();
+        let first = evolution.step();
+        evolution.run(2);
+
+        assert_eq!(evolution.generation(), 3);
+        assert_eq!(evolution.archive().len(), 3);
+        assert!(first
+            .windows(2)
+            .all(|pair| pair[0].fitness >= pair[1].fitness));
+        // The best creature is kept, so the best fitness never decreases
+        assert!(evolution
+            .archive()
+            .windows(2)
+            .all(|pair| pair[1].fitness >= pair[0].fitness));
+
+        // The same seed leads to the same creatures
+        let mut replay = Evolution::new(settings(), 11).unwrap();
+        replay.run(3);
+        assert_eq!(replay.archive(), evolution.archive());
+
+        let empty = Settings {
+            population_size: 0,
+            ..settings()
+        };
+        assert!(Evolution::new(empty, 11).is_err());
+        [(0, 2), (3, 0)].iter().for_each(|&(radius, rings)| {
+            let settings = Settings {
+                radius,
+                rings,
+                ..settings()
+            };
+            assert!(Evolution::new(settings, 11).is_err());
+        });
+    }
+
+    #[test]
+    fn test_json_archive() {
+        let mut evolution = Evolution::new(settings(), 3).unwrap();
+        evolution.run(2);
+
+        let archive: Value = serde_json::from_str(&evolution.archive_to_json()).unwrap();
+        let creatures = archive.as_array().unwrap();
+        assert_eq!(creatures.len(), 2);
+        assert_eq!(creatures[1]["code"], "E1");
+        assert_eq!(creatures[1]["params"]["R"], 3);
+        assert_eq!(creatures[1]["params"]["kn"], 2);
+
+        // Other kernel cores are not supported
+        let mut polynomial = creatures[1].clone();
+        polynomial["params"]["kn"] = 1.into();
+        assert!(Genome::from_json(&polynomial).is_err());
+
+        // Parameters leading to a NaN kernel are rejected
+        let mut no_radius = creatures[1].clone();
+        no_radius["params"]["R"] = 0.into();
+        assert!(Genome::from_json(&no_radius).is_err());
+        let mut no_weight = creatures[1].clone();
+        no_weight["params"]["b"] = "0,0".into();
+        assert!(Genome::from_json(&no_weight).is_err());
+
+        creatures
+            .iter()
+            .zip(evolution.archive())
+            .for_each(|(json, creature)| {
+                let genome = Genome::from_json(json).unwrap();
+                assert_eq!(genome.parameters, creature.genome.parameters);
+
+                // Cells are stored on 255 levels
+                let patch = &creature.genome.patch;
+                assert_eq!(
+                    (genome.patch.width, genome.patch.height),
+                    (patch.width, patch.height)
+                );
+                genome
+                    .patch
+                    .iter()
+                    .zip(patch.iter())
+                    .for_each(|(a, b)| assert!((a - b).abs() <= 0.5 / LEVELS));
+            });
+    }
+
+    #[test]
+    fn test_cells_encoding() {
+        let patch = Matrix::from_vec(
+            vec![0.0, 1.0, 1.0, 0.0, 0.0, 0.0, 4.0 / 255.0, 0.0, 0.0],
+            3,
+            3,
+        )
+        .unwrap();
+        assert_eq!(encode_cells(&patch), ".2yO$$D!");
+        assert_eq!(decode_cells(".2yO$$D!").unwrap(), patch);
+
+        // Multiple rows, fractions and the Life symbols
+        let decoded = decode_cells("2o$2$b.pA!").unwrap();
+        assert_eq!((decoded.width, decoded.height), (3, 4));
+        assert_eq!(decoded.m[0..3], [1.0, 1.0, 0.0]);
+        assert_eq!(decoded.m[11], 25.0 / 255.0);
+        assert_eq!(parse_fraction("2/3").unwrap(), 2.0 / 3.0);
+        assert!(decode_cells("2Z!").is_err());
+
+        // Run lengths are bounded
+        assert!(decode_cells("99999999999999999999999o!").is_err());
+        assert!(decode_cells("1025o!").is_err());
+        assert!(decode_cells("600o600o!").is_err());
+        assert!(decode_cells("1024$o!").is_err());
+        assert_eq!(decode_cells("1024o!").unwrap().width, 1024);
+    }
+}
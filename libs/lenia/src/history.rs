@@ -0,0 +1,56 @@
+use std::mem::size_of;
+
+use maths::{history::Cost, matrix::Matrix};
+
+use crate::statistics::Statistics;
+
+/// Default memory budget of the history (in bytes)
+pub const DEFAULT_HISTORY_BUDGET: usize = 16 * 1024 * 1024;
+
+/// State of a Lenia world at a given step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub state: Matrix<f64>,
+    pub statistics: Statistics,
+}
+
+impl Cost for Snapshot {
+    fn cost(&self) -> usize {
+        size_of::<Self>() + self.state.m.len() * size_of::<f64>()
+    }
+}
+
+/// Bounded history of the previous states of a Lenia world
+///
+/// Every cell of a Lenia world changes at each step, so each step is
+/// stored as a full snapshot.
+pub type History = maths::history::History<Snapshot>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn snapshot(step: u32) -> Snapshot {
+        Snapshot {
+            state: Matrix::from_constant(4, 4, step as f64),
+            statistics: Statistics {
+                step,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_budget() {
+        let cost = snapshot(0).cost();
+        let mut history = History::new(2 * cost);
+
+        history.push(snapshot(0));
+        history.push(snapshot(1));
+        history.push(snapshot(2));
+        // The oldest snapshot has been forgotten
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.size(), 2 * cost);
+        assert_eq!(history.pop(), Some(snapshot(2)));
+    }
+}
@@ -0,0 +1,128 @@
+use maths::matrix::Matrix;
+use wasm_bindgen::prelude::*;
+
+/// Numerical scheme used to integrate the state of a world over time
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Integrator {
+    /// Explicit Euler: `A + dt·G(K*A)`, the original Lenia update
+    #[default]
+    Euler = 0,
+    /// Heun's method, a second order Runge-Kutta
+    Heun = 1,
+    /// Classic fourth order Runge-Kutta
+    RungeKutta4 = 2,
+    /// Asymptotic Lenia: `A + dt·(target − A)`, the target being the
+    /// growth mapped to [0, 1]
+    Asymptotic = 3,
+}
+
+impl Integrator {
+    /// Compute the state after a step of `dt`, `rate` being the time
+    /// derivative of a state, and `k1` the derivative of `state`
+    ///
+    /// The asymptotic update is an Euler step, only its
+    /// rate differs (see `Integrator::is_asymptotic`).
+    pub fn step<F>(&self, state: &Matrix<f64>, k1: Matrix<f64>, dt: f64, rate: F) -> Matrix<f64>
+    where
+        F: Fn(&Matrix<f64>) -> Matrix<f64>,
+    {
+        match self {
+            Integrator::Euler | Integrator::Asymptotic => state.add_scaled(&k1, dt),
+            Integrator::Heun => {
+                let k2 = rate(&state.add_scaled(&k1, dt));
+
+                state.add_scaled(&k1.add_scaled(&k2, 1.0), dt / 2.0)
+            }
+            Integrator::RungeKutta4 => {
+                let k2 = rate(&state.add_scaled(&k1, dt / 2.0));
+                let k3 = rate(&state.add_scaled(&k2, dt / 2.0));
+                let k4 = rate(&state.add_scaled(&k3, dt));
+
+                let slope = k1
+                    .add_scaled(&k4, 1.0)
+                    .add_scaled(&k2.add_scaled(&k3, 1.0), 2.0);
+                state.add_scaled(&slope, dt / 6.0)
+            }
+        }
+    }
+
+    /// Denote if the rate of a cell is `target − A` rather than the growth
+    pub fn is_asymptotic(&self) -> bool {
+        *self == Integrator::Asymptotic
+    }
+}
+
+/// Smooth alternative to `clamp(0, 1)`: `softplus(k·x)/k − softplus(k·(x−1))/k`
+///
+/// Close to the identity inside [0, 1], it only bends near the bounds,
+/// which it misses by `ln(2)/k`. The larger the steepness, the closer
+/// to a hard clip. The steepness must be finite and positive.
+pub fn soft_clip(value: f64, steepness: f64) -> f64 {
+    (softplus(steepness * value) - softplus(steepness * (value - 1.0))) / steepness
+}
+
+/// Compute `ln(1 + eˣ)` without overflowing for large `x`
+fn softplus(x: f64) -> f64 {
+    x.max(0.0) + (-x.abs()).exp().ln_1p()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Error at t = 1 when integrating dA/dt = -A from A = 1
+    fn error(integrator: Integrator, dt: f64) -> f64 {
+        let mut state = Matrix::from_constant(1, 1, 1.0);
+        let steps = (1.0 / dt).round() as usize;
+
+        let rate = |state: &Matrix<f64>| Matrix {
+            width: 1,
+            height: 1,
+            m: vec![-state.m[0]],
+        };
+
+        for _ in 0..steps {
+            state = integrator.step(&state, rate(&state), dt, rate);
+        }
+
+        (state.m[0] - (-1.0f64).exp()).abs()
+    }
+
+    #[test]
+    fn test_convergence_order() {
+        // Halving dt divides the error by 2 to the order of the scheme
+        let data = [
+            (Integrator::Euler, 2.0),
+            (Integrator::Heun, 4.0),
+            (Integrator::RungeKutta4, 16.0),
+        ];
+
+        data.iter().for_each(|&(integrator, expected_ratio)| {
+            let ratio = error(integrator, 0.02) / error(integrator, 0.01);
+            assert!(
+                (ratio - expected_ratio).abs() < 0.1 * expected_ratio,
+                "{:?}: {}",
+                integrator,
+                ratio
+            );
+        });
+    }
+
+    #[test]
+    fn test_soft_clip() {
+        // Interior values are nearly unchanged
+        [0.1, 0.3, 0.5, 0.7, 0.9].iter().for_each(|&value| {
+            assert!((soft_clip(value, 50.0) - value).abs() < 1e-3, "{}", value);
+        });
+
+        // Bounds are only missed by ln(2)/k, and never crossed
+        assert!((soft_clip(0.0, 50.0) - 2f64.ln() / 50.0).abs() < 1e-10);
+        assert!((soft_clip(1.0, 50.0) - (1.0 - 2f64.ln() / 50.0)).abs() < 1e-10);
+        assert!(soft_clip(-1.0, 50.0) > 0.0);
+        assert!(soft_clip(2.0, 50.0) <= 1.0);
+        assert!((soft_clip(100.0, 50.0) - 1.0).abs() < 1e-10);
+        assert!(soft_clip(0.6, 4.0) > soft_clip(0.4, 4.0));
+    }
+}
@@ -0,0 +1,234 @@
+use js_sys::Float64Array;
+use maths::{
+    convolution::{convolve_3d, gaussian_kernel_3d},
+    coordinate::Coordinate3,
+    function::gauss,
+    matrix::Matrix3,
+    random::Random,
+};
+use wasm_bindgen::prelude::*;
+
+use crate::utils::set_panic_hook;
+
+/// Lenia world in 3 dimensions: a toroidal cube of cells
+///
+/// A step is the Euler update `clamp(A + dt·G(K*A), 0, 1)` of the 2D
+/// world, with a spherical kernel. Cross-sections of the cube can be
+/// read along each axis, to be displayed as 2D heatmaps.
+#[wasm_bindgen]
+pub struct Lenia3D {
+    size: usize,
+    time_step: f64,
+    step: u32,
+    state: Matrix3<f64>,
+    convolution_kernel: Matrix3<f64>,
+    growth_function: fn(&f64) -> f64,
+}
+
+#[wasm_bindgen]
+impl Lenia3D {
+    pub fn evolve(&mut self) {
+        let potential = self.potential();
+
+        self.state
+            .iter_mut()
+            .zip(&potential.m)
+            .for_each(|(value, potential)| {
+                *value =
+                    (*value + self.time_step * (self.growth_function)(potential)).clamp(0.0, 1.0)
+            });
+        self.step += 1;
+    }
+
+    /// Replace the state by a ball of uniform random values
+    /// at the center of the world
+    ///
+    /// The same seed always generates the same ball.
+    pub fn randomize(&mut self, seed: u32, radius: f64) {
+        let mut random = Random::new(seed as u64);
+        let center = (self.size as f64 - 1.0) / 2.0;
+
+        self.state = Matrix3::from_function(self.size, self.size, self.size, |x, y, z| {
+            let value = random.next_f64();
+            let distance = [x, y, z]
+                .iter()
+                .map(|&position| (position as f64 - center).powi(2))
+                .sum::<f64>()
+                .sqrt();
+
+            if distance > radius {
+                0.0
+            } else {
+                value
+            }
+        });
+        self.step = 0;
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Number of evolution steps since the last reset
+    pub fn step(&self) -> u32 {
+        self.step
+    }
+
+    /// Sum of the values of all the cells
+    pub fn mass(&self) -> f64 {
+        self.state.iter().sum()
+    }
+
+    /// Pointer to the state, stored plane by plane along the z axis
+    pub fn state(&self) -> *const f64 {
+        self.state.m.as_ptr()
+    }
+
+    /// Number of cells behind the `state` pointer
+    pub fn state_len(&self) -> usize {
+        self.state.m.len()
+    }
+
+    /// Copy of the state, plane by plane along the z axis
+    pub fn state_array(&self) -> Float64Array {
+        Float64Array::from(self.state.m.as_slice())
+    }
+
+    /// Cross-section of the world at the specified x, row by row along y
+    pub fn slice_x(&self, x: usize) -> Vec<f64> {
+        self.state.slice_x(x).m
+    }
+
+    /// Cross-section of the world at the specified y, row by row along x
+    pub fn slice_y(&self, y: usize) -> Vec<f64> {
+        self.state.slice_y(y).m
+    }
+
+    /// Cross-section of the world at the specified z, row by row along x
+    pub fn slice_z(&self, z: usize) -> Vec<f64> {
+        self.state.slice_z(z).m
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl Lenia3D {
+    pub fn new(
+        size: usize,
+        time_constant: f64,
+        growth_function: fn(&f64) -> f64,
+        convolution_kernel: Matrix3<f64>,
+    ) -> Self {
+        set_panic_hook();
+
+        Self {
+            size,
+            time_step: 1.0 / time_constant,
+            step: 0,
+            state: Matrix3::from_constant(size, size, size, 0.0),
+            convolution_kernel,
+            growth_function,
+        }
+    }
+
+    pub fn get_state(&self) -> &Matrix3<f64> {
+        &self.state
+    }
+
+    /// Replace the whole state of the world
+    pub fn set_state(&mut self, state: Matrix3<f64>) {
+        self.state = state;
+        self.step = 0;
+    }
+
+    /// Convolution of the state by the kernel
+    fn potential(&self) -> Matrix3<f64> {
+        Matrix3::from_function(self.size, self.size, self.size, |x, y, z| {
+            convolve_3d(&Coordinate3(x, y, z), &self.state, &self.convolution_kernel)
+        })
+    }
+}
+
+#[wasm_bindgen]
+pub fn lenia_3d() -> Lenia3D {
+    let kernel = gaussian_kernel_3d(6, 0.5, 0.15);
+    let mut world = Lenia3D::new(32, 10.0, |x| gauss(*x, 2.0, 0.15, 0.015) - 1.0, kernel);
+    world.randomize(0, 8.0);
+
+    world
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn world() -> Lenia3D {
+        Lenia3D::new(
+            12,
+            10.0,
+            |x| gauss(*x, 2.0, 0.2, 0.05) - 1.0,
+            gaussian_kernel_3d(3, 0.5, 0.15),
+        )
+    }
+
+    #[test]
+    fn test_evolve() {
+        let mut world = world();
+        world.randomize(4, 4.0);
+        let initial_mass = world.mass();
+        assert!(initial_mass > 0.0);
+        // The ball does not reach the corners
+        assert_eq!(*world.get_state().get_by_index(0), 0.0);
+
+        world.evolve();
+        world.evolve();
+
+        assert_eq!(world.step(), 2);
+        assert_ne!(world.mass(), initial_mass);
+        assert!(world
+            .get_state()
+            .iter()
+            .all(|value| (0.0..=1.0).contains(value)));
+    }
+
+    #[test]
+    fn test_translation_invariance() {
+        // Evolving a translated state gives the translated evolution
+        let mut world = world();
+        world.randomize(9, 3.0);
+        let state = world.get_state().clone();
+        let shifted = Matrix3::from_function(12, 12, 12, |x, y, z| {
+            *state.get_by_coordinate(&Coordinate3((x + 11) % 12, (y + 2) % 12, (z + 5) % 12))
+        });
+
+        let mut shifted_world = self::world();
+        shifted_world.set_state(shifted);
+        world.evolve();
+        shifted_world.evolve();
+
+        let expected = Matrix3::from_function(12, 12, 12, |x, y, z| {
+            *world.get_state().get_by_coordinate(&Coordinate3(
+                (x + 11) % 12,
+                (y + 2) % 12,
+                (z + 5) % 12,
+            ))
+        });
+        shifted_world
+            .get_state()
+            .iter()
+            .zip(expected.iter())
+            .for_each(|(a, b)| assert!((a - b).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_slices() {
+        let mut world = world();
+        world.set_state(Matrix3::from_function(12, 12, 12, |x, y, z| {
+            (x + 12 * y + 144 * z) as f64
+        }));
+
+        assert_eq!(world.slice_z(3).len(), 144);
+        assert_eq!(world.slice_z(3)[12 + 2], (2 + 12 + 3 * 144) as f64);
+        assert_eq!(world.slice_y(3)[12 + 2], (2 + 3 * 12 + 144) as f64);
+        assert_eq!(world.slice_x(3)[12 + 2], (3 + 2 * 12 + 144) as f64);
+    }
+}
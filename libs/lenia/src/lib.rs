@@ -1,80 +1,259 @@
 use maths::{
-    convolution::{convolute, gaussian_kernel}, coordinate::Coordinate, function::gauss, matrix::Matrix
+    convolution::{
+        cross_section, distance_kernel_center, gaussian_kernel, growth_curve, radial_profile,
+        sparse_correlation, SparseKernel,
+    },
+    function::gauss,
+    matrix::Matrix,
+    random::Random,
 };
+use js_sys::Float64Array;
+use history::{History, Snapshot, DEFAULT_HISTORY_BUDGET};
+use integrator::soft_clip;
 use utils::set_panic_hook;
 use wasm_bindgen::prelude::*;
 
+pub use curve::Curve;
+pub use integrator::Integrator;
+pub use lenia3d::Lenia3D;
+pub use statistics::Statistics;
+
+mod curve;
+pub mod differentiable;
+pub mod evolution;
+mod history;
+pub mod integrator;
+pub mod lenia3d;
+pub mod soup;
+mod statistics;
 mod utils;
+pub mod worker;
+
+/// Size (in cells) of the tiles skipped by the convolution when
+/// they are out of the kernel reach of any non-empty cell
+const CONVOLUTION_TILE_SIZE: usize = 8;
 
 #[wasm_bindgen]
 // Define the Lenia struct
+#[derive(Clone)]
 pub struct Lenia {
     size: usize,
-    time_constant: f64,
+    time_step: f64,
+    integrator: Integrator,
+    soft_clip_steepness: Option<f64>,
     convoluted_state: Matrix<f64>,
     state: Matrix<f64>,
     convolution_kernel: Matrix<f64>,
-    reversed_convolution_kernel: Matrix<f64>,
+    sparse_convolution_kernel: SparseKernel,
     growth_function: fn(&f64) -> f64,
+    statistics: Statistics,
+    history: History,
 }
 
 #[wasm_bindgen]
 impl Lenia {
     pub fn evolve(&mut self) {
-        // Convolution
-        for y in 0..self.size {
-          for x in 0..self.size {
-            let point = Coordinate(x, y);
-            self.convoluted_state.set(&point, convolute(&point, &self.state, &self.reversed_convolution_kernel));
-          }
-        }
+        // Remember the current state to be able to step back
+        self.history.push(Snapshot {
+            state: self.state.clone(),
+            statistics: self.statistics,
+        });
 
-            // Apply growth function
-        for y in 0..self.size {
-          for x in 0..self.size {
-            let point = Coordinate(x, y);
-            let current_state = self.state.get_by_coordinate(&point);
-            let convoluted_state = self.convoluted_state.get_by_coordinate(&point);
-            let next_state = (current_state + (1.0 / self.time_constant) * &(self.growth_function)(convoluted_state)).clamp(0.0, 1.0);
-            self.state.set(&point, next_state);
-          }
-        }
+        // The convolution of the current state is kept to be displayed
+        self.convoluted_state = self.potential(&self.state);
+        let rate = self.rate_from_potential(&self.state, self.convoluted_state.clone());
 
-        // let result: Vec<f64> = self
-        //     .state
-        //     .iter()
-        //     .enumerate()
-        //     // Map index to coordinates
-        //     .map(|(index, _)| self.state.index_to_coordinate(index))
-        //     .map(|point| {
-        //         (
-        //             point,
-        //             convolute(&point, &self.state, &self.reversed_convolution_kernel),
-        //         )
-        //     })
-        //     // Apply growth function
-        //     .map(|(point, convolution_result)| {
-        //         self.state.get_by_coordinate(&point)
-        //             + (1.0 / self.time_constant) * &(self.growth_function)(&convolution_result)
-        //     })
-        //     // clamp
-        //     .map(|val| val.clamp(0.0, 1.0)) // clamp
-        //     .collect();
-
-        // self.state = Matrix::from_vec(result, self.size, self.size).unwrap();
+        let next_state =
+            self.integrator
+                .step(&self.state, rate, self.time_step, |state| self.rate(state));
+        self.state = self.clip(next_state);
+
+        self.statistics = Statistics::measure(
+            &self.state,
+            self.statistics.step + 1,
+            Some(&self.statistics),
+        );
     }
 
+    /// Pointer to the state, row by row
+    ///
+    /// A view built on this pointer over the wasm memory is detached when
+    /// the memory grows: it must be rebuilt, with `state_len`, after every
+    /// call that may allocate (`evolve`, `randomize`...). The `*_array`
+    /// accessors return copies that do not have this issue.
     pub fn state(&self) -> *const f64 {
         self.state.m.as_ptr()
     }
 
+    /// Number of cells behind the `state` and `convoluted_state` pointers
+    pub fn state_len(&self) -> usize {
+        self.state.m.len()
+    }
+
+    /// Copy of the state, row by row
+    pub fn state_array(&self) -> Float64Array {
+        Float64Array::from(self.state.m.as_slice())
+    }
+
     pub fn convoluted_state(&self) -> *const f64 {
         self.convoluted_state.m.as_ptr()
     }
 
+    /// Copy of the convolution of the state by the kernel, row by row
+    pub fn convoluted_state_array(&self) -> Float64Array {
+        Float64Array::from(self.convoluted_state.m.as_slice())
+    }
+
     pub fn convolution_kernel(&self) -> *const f64 {
         self.convolution_kernel.m.as_ptr()
     }
+
+    /// Number of cells of a row of the convolution kernel
+    pub fn convolution_kernel_width(&self) -> usize {
+        self.convolution_kernel.width
+    }
+
+    /// Number of cells behind the `convolution_kernel` pointer
+    pub fn convolution_kernel_len(&self) -> usize {
+        self.convolution_kernel.m.len()
+    }
+
+    /// Copy of the convolution kernel, row by row
+    pub fn convolution_kernel_array(&self) -> Float64Array {
+        Float64Array::from(self.convolution_kernel.m.as_slice())
+    }
+
+    /// Value of the convolution kernel as a function of the
+    /// distance from its center, divided by the kernel radius
+    pub fn kernel_radial_profile(&self) -> Curve {
+        radial_profile(
+            &self.convolution_kernel,
+            &distance_kernel_center(&self.convolution_kernel),
+            (self.convolution_kernel.width / 2) as f64,
+        )
+        .into()
+    }
+
+    /// Row of the convolution kernel through its center
+    pub fn kernel_cross_section(&self) -> Vec<f64> {
+        let center = distance_kernel_center(&self.convolution_kernel);
+
+        // The center is always a cell of the kernel
+        cross_section(&self.convolution_kernel, &center).unwrap()
+    }
+
+    /// Growth function sampled at `samples` potentials over [0, 1]
+    pub fn growth_curve(&self, samples: usize) -> Curve {
+        growth_curve(self.growth_function, samples).into()
+    }
+
+    /// Move the world back to its previous state
+    ///
+    /// Return `false` if the previous state is not in the history anymore.
+    pub fn step_back(&mut self) -> bool {
+        if !self.restore_previous() {
+            return false;
+        }
+
+        self.convoluted_state = self.potential(&self.state);
+        true
+    }
+
+    /// Move the world to the specified step
+    ///
+    /// Past states are restored from the history, future ones are
+    /// computed. Return `false` if the step is older than the history,
+    /// the world is then left at the oldest known state.
+    pub fn seek(&mut self, step: u32) -> bool {
+        while self.statistics.step < step {
+            self.evolve();
+        }
+
+        let mut found = true;
+        let mut restored = false;
+        while self.statistics.step > step {
+            if !self.restore_previous() {
+                found = false;
+                break;
+            }
+            restored = true;
+        }
+
+        // The convolution is only needed for the state reached
+        if restored {
+            self.convoluted_state = self.potential(&self.state);
+        }
+
+        found
+    }
+
+    /// Set the memory (in bytes) that can be used to remember previous states
+    pub fn set_history_budget(&mut self, budget: usize) {
+        self.history.set_budget(budget);
+    }
+
+    /// Number of steps that can be undone with `step_back`
+    pub fn history_length(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Approximate memory (in bytes) used to remember previous states
+    pub fn history_size(&self) -> usize {
+        self.history.size()
+    }
+
+    /// Replace the state by a soup of uniform random values
+    /// in a disc at the center of the world
+    ///
+    /// The same seed always generates the same soup.
+    pub fn randomize(&mut self, seed: u32, radius: f64) {
+        let mut random = Random::new(seed as u64);
+        self.reset_state(soup::uniform_soup(self.size, radius, &mut random));
+    }
+
+    /// Replace the state by a soup of smooth random values
+    /// in a disc at the center of the world
+    ///
+    /// `scale` is the typical size (in cells) of the noise features.
+    /// The same seed always generates the same soup.
+    pub fn randomize_noise(&mut self, seed: u32, radius: f64, scale: usize) {
+        let mut random = Random::new(seed as u64);
+        self.reset_state(soup::noise_soup(self.size, radius, scale, &mut random));
+    }
+
+    /// Metrics of the current state (mass, centroid, velocity...)
+    pub fn statistics(&self) -> Statistics {
+        self.statistics
+    }
+
+    /// Select the numerical scheme used by `evolve`
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    /// Set the duration of a step (`1 / T` by default)
+    pub fn set_time_step(&mut self, time_step: f64) {
+        self.time_step = time_step;
+    }
+
+    pub fn time_step(&self) -> f64 {
+        self.time_step
+    }
+
+    /// Replace the hard clip of the states to [0, 1] by a smooth
+    /// saturation of the specified steepness, or go back to the hard clip
+    ///
+    /// The steepness must be finite and positive.
+    pub fn set_soft_clip(&mut self, steepness: Option<f64>) -> Result<(), String> {
+        if let Some(steepness) = steepness {
+            if !(steepness.is_finite() && steepness > 0.0) {
+                return Err(format!("Invalid soft clip steepness: {}", steepness));
+            }
+        }
+
+        self.soft_clip_steepness = steepness;
+        Ok(())
+    }
 }
 
 impl Lenia {
@@ -168,21 +347,100 @@ impl Lenia {
             ],
         ];
 
-        let mut reversed_convolution_kernel = convolution_kernel.clone();
-        reversed_convolution_kernel.m.reverse();
+        let state = Matrix::from_function(size, size, |x, y| {
+            *orbium.get(y).and_then(|row| row.get(x)).unwrap_or(&0.0)
+        });
 
         Self {
             size,
-            time_constant,
+            time_step: 1.0 / time_constant,
+            integrator: Integrator::default(),
+            soft_clip_steepness: None,
+            sparse_convolution_kernel: SparseKernel::flipped(&convolution_kernel),
             convolution_kernel,
-            reversed_convolution_kernel,
             growth_function,
             convoluted_state: Matrix::from_constant(size, size, 0.0),
-            state: Matrix::from_function(size, size, |x, y| {
-                *orbium.get(y).and_then(|row| row.get(x)).unwrap_or(&0.0)
-            }),
+            statistics: Statistics::measure(&state, 0, None),
+            history: History::new(DEFAULT_HISTORY_BUDGET),
+            state,
         }
     }
+
+    /// Convolution of a state by the kernel: the correlation
+    /// by the flipped sparse kernel
+    fn potential(&self, state: &Matrix<f64>) -> Matrix<f64> {
+        sparse_correlation(state, &self.sparse_convolution_kernel, CONVOLUTION_TILE_SIZE)
+    }
+
+    /// Time derivative of a state for the selected integrator: the growth
+    /// of the potential, or the distance to the growth mapped to [0, 1]
+    /// for the asymptotic update
+    fn rate(&self, state: &Matrix<f64>) -> Matrix<f64> {
+        self.rate_from_potential(state, self.potential(state))
+    }
+
+    /// Time derivative of a state, knowing its convolution by the kernel
+    fn rate_from_potential(&self, state: &Matrix<f64>, potential: Matrix<f64>) -> Matrix<f64> {
+        let mut rate = potential;
+
+        rate.m.iter_mut().zip(&state.m).for_each(|(rate, value)| {
+            let growth = (self.growth_function)(rate);
+            *rate = if self.integrator.is_asymptotic() {
+                (growth + 1.0) / 2.0 - value
+            } else {
+                growth
+            };
+        });
+
+        rate
+    }
+
+    /// Bring the values of a state back to [0, 1]
+    fn clip(&self, mut state: Matrix<f64>) -> Matrix<f64> {
+        state.m.iter_mut().for_each(|value| {
+            *value = match self.soft_clip_steepness {
+                Some(steepness) => soft_clip(*value, steepness),
+                None => value.clamp(0.0, 1.0),
+            }
+        });
+
+        state
+    }
+
+    /// Restore the last state of the history, without its convolution
+    ///
+    /// Return `false` if the history is empty.
+    fn restore_previous(&mut self) -> bool {
+        let Some(snapshot) = self.history.pop() else {
+            return false;
+        };
+
+        self.state = snapshot.state;
+        self.statistics = snapshot.statistics;
+
+        true
+    }
+
+    /// Replace the whole state of the world, and restart its statistics
+    fn reset_state(&mut self, state: Matrix<f64>) {
+        self.statistics = Statistics::measure(&state, 0, None);
+        self.convoluted_state = Matrix::from_constant(self.size, self.size, 0.0);
+        self.history.clear();
+        self.state = state;
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl Lenia {
+    /// Get the values of the state, row by row
+    pub fn get_state(&self) -> &[f64] {
+        &self.state.m
+    }
+
+    /// Get the values of the convolution of the state by the kernel, row by row
+    pub fn get_convoluted_state(&self) -> &[f64] {
+        &self.convoluted_state.m
+    }
 }
 
 #[wasm_bindgen]
@@ -195,3 +453,152 @@ pub fn lenia() -> Lenia {
         kernel,
     )
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_kernel_plots() {
+        let world = lenia();
+
+        let profile = world.kernel_radial_profile();
+        assert_eq!((profile.x()[0], profile.y()[0]), (0.0, 0.0));
+        // The kernel shell peaks around half its radius
+        let peak = profile
+            .y()
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(index, _)| profile.x()[index])
+            .unwrap();
+        assert!((peak - 0.5).abs() < 1.0 / 13.0);
+
+        let section = world.kernel_cross_section();
+        assert_eq!(section.len(), 26);
+        assert_eq!(section[12], 0.0);
+        (1..12).for_each(|offset| assert_eq!(section[12 - offset], section[12 + offset]));
+
+        let growth = world.growth_curve(21);
+        assert_eq!(growth.len(), 21);
+        assert_eq!(growth.y()[3], 1.0);
+        assert!((growth.y()[20] + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_step_back() {
+        let mut world = lenia();
+        world.set_history_budget(2 * world.state.m.len() * std::mem::size_of::<f64>() + 1024);
+        let initial_state = world.state.clone();
+
+        assert!(world.seek(3));
+        assert_eq!(world.statistics().step, 3);
+        let third_state = world.state.clone();
+        assert_eq!(world.history_length(), 2);
+
+        // Only the two last steps can be undone
+        assert!(!world.seek(0));
+        assert_eq!(world.statistics().step, 1);
+        assert_ne!(world.state, initial_state);
+
+        assert!(world.seek(3));
+        assert_eq!(world.state, third_state);
+        assert!(world.step_back());
+        assert_eq!(world.statistics().step, 2);
+
+        // The convolution follows the restored state
+        assert_eq!(world.convoluted_state, world.potential(&world.state));
+        assert!(!world.seek(0));
+        assert_eq!(world.convoluted_state, world.potential(&world.state));
+    }
+
+    /// State of a small world after a duration of 0.4, with time steps of `dt`
+    fn integrate(integrator: Integrator, dt: f64) -> Matrix<f64> {
+        let mut world = Lenia::new(
+            32,
+            10.0,
+            |x| gauss(*x, 2.0, 0.15, 0.015) - 1.0,
+            gaussian_kernel(5, 0.5, 0.15),
+        );
+        world.set_integrator(integrator);
+        world.set_time_step(dt);
+
+        (0..(0.4 / dt).round() as usize).for_each(|_| world.evolve());
+        world.state
+    }
+
+    fn max_difference(a: &Matrix<f64>, b: &Matrix<f64>) -> f64 {
+        a.m.iter()
+            .zip(&b.m)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max)
+    }
+
+    #[test]
+    fn test_continuum_limit() {
+        // Halving the time step moves the state less and less: every
+        // scheme converges to the continuous time solution
+        let integrators = [
+            Integrator::Euler,
+            Integrator::Heun,
+            Integrator::RungeKutta4,
+            Integrator::Asymptotic,
+        ];
+
+        integrators.iter().for_each(|&integrator| {
+            let states: Vec<Matrix<f64>> = [0.1, 0.05, 0.025]
+                .iter()
+                .map(|&dt| integrate(integrator, dt))
+                .collect();
+
+            let coarse = max_difference(&states[0], &states[1]);
+            let fine = max_difference(&states[1], &states[2]);
+            assert!(fine < 0.75 * coarse, "{:?}: {} {}", integrator, coarse, fine);
+        });
+
+        // Higher order schemes are closer to the limit for the same step
+        let reference = integrate(Integrator::RungeKutta4, 0.025);
+        assert!(
+            max_difference(&integrate(Integrator::Heun, 0.1), &reference)
+                < max_difference(&integrate(Integrator::Euler, 0.1), &reference)
+        );
+    }
+
+    #[test]
+    fn test_soft_clip() {
+        let mut hard = lenia();
+        let mut soft = lenia();
+        soft.set_soft_clip(Some(50.0)).unwrap();
+        hard.evolve();
+        soft.evolve();
+
+        // Values stay in [0, 1] and only move near the bounds:
+        // empty space does not fill with mass
+        let bend = 2f64.ln() / 50.0;
+        hard.state.m.iter().zip(&soft.state.m).for_each(|(&hard, &soft)| {
+            assert!(soft > 0.0 && soft < 1.0);
+            assert!((soft - hard).abs() <= bend + 1e-9, "{} {}", hard, soft);
+        });
+
+        // The steepness must be finite and positive
+        [0.0, -8.0, f64::NAN, f64::INFINITY].iter().for_each(|&steepness| {
+            assert!(soft.set_soft_clip(Some(steepness)).is_err());
+        });
+        assert_eq!(soft.soft_clip_steepness, Some(50.0));
+        assert!(soft.set_soft_clip(None).is_ok());
+    }
+
+    #[test]
+    fn test_buffer_lengths() {
+        let mut world = lenia();
+        assert_eq!(world.state_len(), 64 * 64);
+        assert_eq!(world.convolution_kernel_len(), 26 * 26);
+        assert_eq!(world.convolution_kernel_width(), 26);
+
+        // Lengths follow the buffers whatever happens to the world
+        world.evolve();
+        world.randomize(1, 10.0);
+        assert_eq!(world.state_len(), world.state.m.len());
+        assert_eq!(world.state_len(), world.convoluted_state.m.len());
+    }
+}
@@ -0,0 +1,65 @@
+use maths::{
+    function::distance,
+    matrix::Matrix,
+    random::{value_noise, Random},
+};
+
+/// Generate a square world of uniform random values, restricted
+/// to a disc of the specified radius at the center of the world
+pub fn uniform_soup(size: usize, radius: f64, random: &mut Random) -> Matrix<f64> {
+    let noise = Matrix::from_function(size, size, |_, _| random.next_f64());
+
+    restrict_to_disc(noise, radius)
+}
+
+/// Generate a square world of smooth random values, restricted
+/// to a disc of the specified radius at the center of the world
+///
+/// `scale` is the typical size (in cells) of the noise features.
+pub fn noise_soup(size: usize, radius: f64, scale: usize, random: &mut Random) -> Matrix<f64> {
+    restrict_to_disc(value_noise(size, size, scale, random), radius)
+}
+
+/// Set to 0 all the values outside of the disc of
+/// the specified radius at the center of the matrix
+fn restrict_to_disc(mut matrix: Matrix<f64>, radius: f64) -> Matrix<f64> {
+    let center = (
+        (matrix.width as f64 - 1.0) / 2.0,
+        (matrix.height as f64 - 1.0) / 2.0,
+    );
+    let width = matrix.width;
+
+    matrix.iter_mut().enumerate().for_each(|(index, value)| {
+        let point = ((index % width) as f64, (index / width) as f64);
+        if distance(point, center) > radius {
+            *value = 0.0;
+        }
+    });
+
+    matrix
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_uniform_soup() {
+        let soup = uniform_soup(16, 4.0, &mut Random::new(3));
+
+        assert_eq!(soup, uniform_soup(16, 4.0, &mut Random::new(3)));
+        // Corners are outside of the disc
+        assert_eq!(*soup.get_by_index(0), 0.0);
+        assert_eq!(*soup.get_by_index(16 * 16 - 1), 0.0);
+        assert!(soup.iter().any(|value| *value > 0.0));
+    }
+
+    #[test]
+    fn test_noise_soup() {
+        let soup = noise_soup(16, 4.0, 4, &mut Random::new(3));
+
+        assert_eq!(soup, noise_soup(16, 4.0, 4, &mut Random::new(3)));
+        assert_ne!(soup, noise_soup(16, 4.0, 4, &mut Random::new(4)));
+        assert_eq!(*soup.get_by_index(0), 0.0);
+    }
+}
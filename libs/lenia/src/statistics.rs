@@ -0,0 +1,140 @@
+use std::f64::consts::PI;
+
+use maths::{
+    matrix::Matrix,
+    statistics::{periodic_difference, toroidal_centroid, toroidal_variance},
+};
+use wasm_bindgen::prelude::*;
+
+/// Metrics describing the state of a Lenia world at a given step
+///
+/// Positions are expressed in cells, on the toroidal world: the centroid
+/// stays correct when a creature crosses the world edges.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Statistics {
+    /// Number of evolution steps since the world creation
+    pub step: u32,
+    /// Sum of the values of all the cells
+    pub mass: f64,
+    /// Center of mass on the x axis
+    pub centroid_x: f64,
+    /// Center of mass on the y axis
+    pub centroid_y: f64,
+    /// Displacement of the center of mass on the x axis since the previous step
+    pub velocity_x: f64,
+    /// Displacement of the center of mass on the y axis since the previous step
+    pub velocity_y: f64,
+    /// Rotation of the velocity direction since the previous step (rad/step)
+    pub angular_speed: f64,
+    /// Mass weighted variance of the distance to the center of mass
+    pub mass_variance: f64,
+}
+
+#[wasm_bindgen]
+impl Statistics {
+    /// Norm of the velocity vector (cells/step)
+    pub fn speed(&self) -> f64 {
+        self.velocity_x.hypot(self.velocity_y)
+    }
+}
+
+impl Statistics {
+    /// Measure the statistics of a world state
+    ///
+    /// Velocity and angular speed are derived from the `previous` statistics,
+    /// they are null when there is no previous measure or when the world
+    /// was empty.
+    pub fn measure(state: &Matrix<f64>, step: u32, previous: Option<&Statistics>) -> Self {
+        let mass = state.iter().sum();
+
+        let Some((centroid_x, centroid_y)) = toroidal_centroid(state) else {
+            return Self {
+                step,
+                ..Default::default()
+            };
+        };
+
+        let (velocity_x, velocity_y) = match previous {
+            Some(previous) if previous.mass > 0.0 => (
+                periodic_difference(previous.centroid_x, centroid_x, state.width as f64),
+                periodic_difference(previous.centroid_y, centroid_y, state.height as f64),
+            ),
+            _ => (0.0, 0.0),
+        };
+
+        let angular_speed = match previous {
+            Some(previous) if previous.speed() > 0.0 && (velocity_x, velocity_y) != (0.0, 0.0) => {
+                periodic_difference(
+                    previous.velocity_y.atan2(previous.velocity_x),
+                    velocity_y.atan2(velocity_x),
+                    2.0 * PI,
+                )
+            }
+            _ => 0.0,
+        };
+
+        Self {
+            step,
+            mass,
+            centroid_x,
+            centroid_y,
+            velocity_x,
+            velocity_y,
+            angular_speed,
+            mass_variance: toroidal_variance(state, (centroid_x, centroid_y)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn square_at(x: usize, y: usize) -> Matrix<f64> {
+        Matrix::from_function(16, 16, |i, j| {
+            if (x..x + 2).contains(&i) && (y..y + 2).contains(&j) {
+                0.5
+            } else {
+                0.0
+            }
+        })
+    }
+
+    #[test]
+    fn test_measure_empty_world() {
+        let statistics = Statistics::measure(&Matrix::from_constant(8, 8, 0.0), 3, None);
+
+        assert_eq!(
+            statistics,
+            Statistics {
+                step: 3,
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_measure_moving_mass() {
+        let first = Statistics::measure(&square_at(4, 4), 0, None);
+        assert_eq!(first.mass, 2.0);
+        assert!((first.centroid_x - 4.5).abs() < 1e-9);
+        assert!((first.centroid_y - 4.5).abs() < 1e-9);
+        assert_eq!(first.speed(), 0.0);
+        assert!((first.mass_variance - 0.5).abs() < 1e-9);
+
+        // Move right
+        let second = Statistics::measure(&square_at(5, 4), 1, Some(&first));
+        assert!((second.velocity_x - 1.0).abs() < 1e-9);
+        assert!(second.velocity_y.abs() < 1e-9);
+        assert_eq!(second.angular_speed, 0.0);
+
+        // Move down: the direction turned by a quarter
+        let third = Statistics::measure(&square_at(5, 5), 2, Some(&second));
+        assert!((third.angular_speed - PI / 2.0).abs() < 1e-9);
+
+        // Move up across the world edge
+        let fourth = Statistics::measure(&square_at(5, 14), 3, Some(&third));
+        assert!((fourth.velocity_y + 7.0).abs() < 1e-9);
+    }
+}
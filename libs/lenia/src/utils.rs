@@ -9,11 +9,33 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-// A macro to provide `println!(..)`-style syntax for `console.log` logging.
+/// Write a log message to the backend selected by cargo feature:
+/// the browser console with `console_log`, the standard error with
+/// `native_log`, and nowhere otherwise (messages are then not even formatted)
+#[cfg(feature = "console_log")]
+#[allow(dead_code)]
+pub fn log_message(message: std::fmt::Arguments) {
+    web_sys::console::log_1(&message.to_string().into());
+}
+
+#[cfg(all(feature = "native_log", not(feature = "console_log")))]
+#[allow(dead_code)]
+pub fn log_message(message: std::fmt::Arguments) {
+    eprintln!("{}", message);
+}
+
+#[cfg(not(any(feature = "console_log", feature = "native_log")))]
+#[inline(always)]
+#[allow(dead_code)]
+pub fn log_message(_message: std::fmt::Arguments) {}
+
+// A macro to provide `println!(..)`-style syntax for logging.
+#[allow(unused_macros)]
 macro_rules! log {
     ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
+        crate::utils::log_message(format_args!( $( $t )* ))
     }
 }
 
+#[allow(unused_imports)]
 pub(crate) use log;
@@ -0,0 +1,289 @@
+use js_sys::SharedArrayBuffer;
+use wasm_bindgen::prelude::*;
+use worker::{
+    buffer::{DoubleBuffer, SharedMemory},
+    codec::{Decoder, Encoder},
+    in_process::Handler,
+    protocol::{Client, Simulation, Worker},
+    shared_array::SharedArrayMemory,
+};
+
+use crate::{lenia, Lenia};
+
+/// Commands sent by the main thread to a Lenia world running in a worker
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Evolve { steps: u32 },
+    StepBack,
+    Randomize { seed: u32, radius: f64 },
+    SetTimeStep(f64),
+    Snapshot,
+}
+
+impl Command {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Command::Evolve { steps } => Encoder::new().u8(0).u32(*steps),
+            Command::StepBack => Encoder::new().u8(1),
+            Command::Randomize { seed, radius } => Encoder::new().u8(2).u32(*seed).f64(*radius),
+            Command::SetTimeStep(time_step) => Encoder::new().u8(3).f64(*time_step),
+            Command::Snapshot => Encoder::new().u8(4),
+        }
+        .finish()
+    }
+
+    pub fn decode(message: &[u8]) -> Result<Self, String> {
+        let mut decoder = Decoder::new(message);
+        let command = match decoder.u8()? {
+            0 => Command::Evolve {
+                steps: decoder.u32()?,
+            },
+            1 => Command::StepBack,
+            2 => Command::Randomize {
+                seed: decoder.u32()?,
+                radius: decoder.f64()?,
+            },
+            3 => Command::SetTimeStep(decoder.f64()?),
+            4 => Command::Snapshot,
+            tag => return Err(format!("Unknown command: {}", tag)),
+        };
+        decoder.finish()?;
+
+        Ok(command)
+    }
+}
+
+impl Simulation for Lenia {
+    fn apply(&mut self, message: &[u8]) -> Result<(), String> {
+        match Command::decode(message)? {
+            Command::Evolve { steps } => (0..steps).for_each(|_| self.evolve()),
+            Command::StepBack => {
+                self.step_back();
+            }
+            Command::Randomize { seed, radius } => self.randomize(seed, radius),
+            Command::SetTimeStep(time_step) => {
+                if !(time_step.is_finite() && time_step > 0.0) {
+                    return Err(format!("Invalid time step: {}", time_step));
+                }
+                self.set_time_step(time_step)
+            }
+            Command::Snapshot => (),
+        }
+
+        Ok(())
+    }
+
+    fn generation(&self) -> u32 {
+        self.statistics().step
+    }
+
+    /// The state, row by row, as little endian floats
+    fn frame(&self) -> Vec<u8> {
+        self.state
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+}
+
+/// Lenia world running in a Web Worker, driven by encoded commands
+///
+/// The state is sent back in each response, or published in a shared
+/// buffer when one is attached, to avoid copying it through messages.
+#[wasm_bindgen]
+pub struct LeniaWorker {
+    worker: Worker<Lenia>,
+}
+
+#[wasm_bindgen]
+impl LeniaWorker {
+    /// Worker running the default world (see `lenia`)
+    pub fn new() -> LeniaWorker {
+        LeniaWorker::from_world(lenia())
+    }
+
+    /// Publish the next states in a `SharedArrayBuffer` of at least
+    /// `shared_buffer_bytes` bytes
+    pub fn attach_shared_buffer(&mut self, buffer: &SharedArrayBuffer) -> Result<(), String> {
+        self.attach_memory(Box::new(SharedArrayMemory::new(buffer)))
+    }
+
+    /// Apply an encoded command, and return the encoded response
+    pub fn handle(&mut self, message: &[u8]) -> Vec<u8> {
+        self.worker.handle(message)
+    }
+}
+
+impl Default for LeniaWorker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LeniaWorker {
+    /// Publish the next states in a shared memory
+    pub fn attach_memory(&mut self, memory: Box<dyn SharedMemory>) -> Result<(), String> {
+        self.worker.attach_memory(memory)
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl LeniaWorker {
+    pub fn from_world(world: Lenia) -> Self {
+        Self {
+            worker: Worker::new(world),
+        }
+    }
+}
+
+impl Handler for LeniaWorker {
+    fn handle(&mut self, message: &[u8]) -> Vec<u8> {
+        LeniaWorker::handle(self, message)
+    }
+}
+
+/// Main thread side of a `LeniaWorker`: encodes the commands, and
+/// reads the states from the responses or from the shared buffer
+#[wasm_bindgen]
+pub struct LeniaClient {
+    client: Client,
+}
+
+#[wasm_bindgen]
+impl LeniaClient {
+    pub fn new(size: usize) -> LeniaClient {
+        LeniaClient {
+            client: Client::new(vec![0; size * size * std::mem::size_of::<f64>()]),
+        }
+    }
+
+    /// Size (in bytes) of the `SharedArrayBuffer` shared with the worker
+    pub fn shared_buffer_bytes(size: usize) -> usize {
+        DoubleBuffer::required_bytes(size * size * std::mem::size_of::<f64>())
+    }
+
+    pub fn attach_shared_buffer(&mut self, buffer: &SharedArrayBuffer) -> Result<(), String> {
+        self.attach_memory(Box::new(SharedArrayMemory::new(buffer)))
+    }
+
+    pub fn evolve(steps: u32) -> Vec<u8> {
+        Command::Evolve { steps }.encode()
+    }
+
+    pub fn step_back() -> Vec<u8> {
+        Command::StepBack.encode()
+    }
+
+    pub fn randomize(seed: u32, radius: f64) -> Vec<u8> {
+        Command::Randomize { seed, radius }.encode()
+    }
+
+    pub fn set_time_step(time_step: f64) -> Vec<u8> {
+        Command::SetTimeStep(time_step).encode()
+    }
+
+    pub fn snapshot() -> Vec<u8> {
+        Command::Snapshot.encode()
+    }
+
+    /// Read a response of the worker, updating the step and the state
+    pub fn receive(&mut self, message: &[u8]) -> Result<(), String> {
+        self.client.receive(message)
+    }
+
+    pub fn step(&self) -> u32 {
+        self.client.generation()
+    }
+
+    /// Copy of the last state received, row by row
+    pub fn state(&self) -> Vec<f64> {
+        self.client
+            .frame()
+            .chunks_exact(8)
+            .map(|value| f64::from_le_bytes(value.try_into().unwrap()))
+            .collect()
+    }
+}
+
+impl LeniaClient {
+    /// Read the states published by the worker in a shared memory
+    pub fn attach_memory(&mut self, memory: Box<dyn SharedMemory>) -> Result<(), String> {
+        self.client.attach_memory(memory)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use worker::{buffer::InProcessMemory, in_process::InProcessWorker};
+
+    #[test]
+    fn test_commands() {
+        [
+            Command::Evolve { steps: 3 },
+            Command::StepBack,
+            Command::Randomize {
+                seed: 4,
+                radius: 12.5,
+            },
+            Command::SetTimeStep(0.05),
+            Command::Snapshot,
+        ]
+        .into_iter()
+        .for_each(|command| assert_eq!(Command::decode(&command.encode()), Ok(command)));
+
+        assert!(Command::decode(&[7]).is_err());
+    }
+
+    #[test]
+    fn test_messages() {
+        let worker = InProcessWorker::spawn(LeniaWorker::new);
+        let mut client = LeniaClient::new(64);
+
+        client
+            .receive(&worker.request(LeniaClient::evolve(2)))
+            .unwrap();
+        client
+            .receive(&worker.request(LeniaClient::step_back()))
+            .unwrap();
+
+        // The worker state is the state of a world evolved in place
+        let mut world = lenia();
+        world.evolve();
+        assert_eq!(client.step(), 1);
+        assert_eq!(client.state(), world.state.m);
+
+        [0.0, f64::NAN, f64::INFINITY]
+            .into_iter()
+            .for_each(|time_step| {
+                assert!(client
+                    .receive(&worker.request(LeniaClient::set_time_step(time_step)))
+                    .is_err())
+            });
+    }
+
+    #[test]
+    fn test_shared_buffer() {
+        let memory = InProcessMemory::new(LeniaClient::shared_buffer_bytes(64));
+        let worker_memory = memory.clone();
+        let worker = InProcessWorker::spawn(move || {
+            let mut worker = LeniaWorker::new();
+            worker.attach_memory(Box::new(worker_memory)).unwrap();
+            worker
+        });
+        let mut client = LeniaClient::new(64);
+        client.attach_memory(Box::new(memory)).unwrap();
+
+        // Commands are posted without waiting for the responses
+        worker.post(LeniaClient::randomize(3, 20.0));
+        worker.post(LeniaClient::evolve(1));
+        client.receive(&worker.receive()).unwrap();
+        client.receive(&worker.receive()).unwrap();
+
+        let mut world = lenia();
+        world.randomize(3, 20.0);
+        world.evolve();
+        assert_eq!(client.step(), 1);
+        assert_eq!(client.state(), world.state.m);
+    }
+}
@@ -1,14 +1,38 @@
-//! Test suite for the Web and headless browsers.
-#![cfg(target_arch = "wasm32")]
+//! Golden tests of the Lenia worlds, run natively.
 
 extern crate lenia;
-extern crate wasm_bindgen_test;
 
-use wasm_bindgen_test::*;
+/// Step, mass and centroid of the Orbium of the default world, computed
+/// by the reference implementation `tests/reference/orbium.py`
+const ORBIUM_STATISTICS: [(u32, f64, f64, f64); 4] = [
+    (0, 75.09999999999998, 10.42315914354398, 10.234231031403832),
+    (10, 73.60308728438135, 12.683072520052884, 16.06077949453514),
+    (50, 72.85405846566354, 21.452939742492028, 38.65933006920242),
+    (100, 73.12423195007592, 32.1350433978729, 2.735729227456522),
+];
 
-wasm_bindgen_test_configure!(run_in_browser);
+#[test]
+fn test_orbium_statistics() {
+    let mut world = lenia::lenia();
 
-#[wasm_bindgen_test]
-fn pass() {
-    assert_eq!(1 + 1, 2);
+    ORBIUM_STATISTICS
+        .iter()
+        .for_each(|&(step, mass, centroid_x, centroid_y)| {
+            while world.statistics().step < step {
+                world.evolve();
+            }
+
+            let statistics = world.statistics();
+            assert!((statistics.mass - mass).abs() < 1e-9, "step {}", step);
+            assert!(
+                (statistics.centroid_x - centroid_x).abs() < 1e-9,
+                "step {}",
+                step
+            );
+            assert!(
+                (statistics.centroid_y - centroid_y).abs() < 1e-9,
+                "step {}",
+                step
+            );
+        });
 }
@@ -0,0 +1,110 @@
+"""Reference evolution of the Orbium in the default Lenia world
+
+Independent pure Python implementation of the world built by `lenia()`,
+used to produce the golden values of `tests/lenia.rs`:
+
+    python3 tests/reference/orbium.py
+
+- world: 64×64 torus, Orbium in its top left corner, time step 1/10
+- kernel: 26×26 gaussian shell (mean 0.5, deviation 0.15) of radius 13,
+  centered on its cell (12, 12), normalized to a sum of 1
+- growth: 2·exp(-(u - 0.15)² / (2·0.015²)) - 1, explicit Euler update
+  clipped to [0, 1]
+- centroid: circular mean of the column (or row) masses
+"""
+
+import math
+
+SIZE = 64
+RADIUS = 13
+TIME_STEP = 1 / 10
+STEPS = [0, 10, 50, 100]
+
+ORBIUM = [
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.1, 0.14, 0.1, 0.0, 0.0, 0.03, 0.03, 0.0, 0.0, 0.3, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.24, 0.3, 0.3, 0.18, 0.14, 0.15, 0.16, 0.15, 0.09, 0.2, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.15, 0.34, 0.44, 0.46, 0.38, 0.18, 0.14, 0.11, 0.13, 0.19, 0.18, 0.45, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.06, 0.13, 0.39, 0.5, 0.5, 0.37, 0.06, 0.0, 0.0, 0.0, 0.02, 0.16, 0.68, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.11, 0.17, 0.17, 0.33, 0.4, 0.38, 0.28, 0.14, 0.0, 0.0, 0.0, 0.0, 0.0, 0.18, 0.42, 0.0, 0.0],
+    [0.0, 0.0, 0.09, 0.18, 0.13, 0.06, 0.08, 0.26, 0.32, 0.32, 0.27, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.82, 0.0, 0.0],
+    [0.27, 0.0, 0.16, 0.12, 0.0, 0.0, 0.0, 0.25, 0.38, 0.44, 0.45, 0.34, 0.0, 0.0, 0.0, 0.0, 0.0, 0.22, 0.17, 0.0],
+    [0.0, 0.07, 0.2, 0.02, 0.0, 0.0, 0.0, 0.31, 0.48, 0.57, 0.6, 0.57, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.49, 0.0],
+    [0.0, 0.59, 0.19, 0.0, 0.0, 0.0, 0.0, 0.2, 0.57, 0.69, 0.76, 0.76, 0.49, 0.0, 0.0, 0.0, 0.0, 0.0, 0.36, 0.0],
+    [0.0, 0.58, 0.19, 0.0, 0.0, 0.0, 0.0, 0.0, 0.67, 0.83, 0.9, 0.92, 0.87, 0.12, 0.0, 0.0, 0.0, 0.0, 0.22, 0.07],
+    [0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.7, 0.93, 1.0, 1.0, 1.0, 0.61, 0.0, 0.0, 0.0, 0.0, 0.18, 0.11],
+    [0.0, 0.0, 0.82, 0.0, 0.0, 0.0, 0.0, 0.0, 0.47, 1.0, 1.0, 0.98, 1.0, 0.96, 0.27, 0.0, 0.0, 0.0, 0.19, 0.1],
+    [0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.25, 1.0, 1.0, 0.84, 0.92, 0.97, 0.54, 0.14, 0.04, 0.1, 0.21, 0.05],
+    [0.0, 0.0, 0.0, 0.4, 0.0, 0.0, 0.0, 0.0, 0.09, 0.8, 1.0, 0.82, 0.8, 0.85, 0.63, 0.31, 0.18, 0.19, 0.2, 0.01],
+    [0.0, 0.0, 0.0, 0.36, 0.1, 0.0, 0.0, 0.0, 0.05, 0.54, 0.86, 0.79, 0.74, 0.72, 0.6, 0.39, 0.28, 0.24, 0.13, 0.0],
+    [0.0, 0.0, 0.0, 0.01, 0.3, 0.07, 0.0, 0.0, 0.08, 0.36, 0.64, 0.7, 0.64, 0.6, 0.51, 0.39, 0.29, 0.19, 0.04, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.1, 0.24, 0.14, 0.1, 0.15, 0.29, 0.45, 0.53, 0.52, 0.46, 0.4, 0.31, 0.21, 0.08, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.21, 0.21, 0.22, 0.29, 0.36, 0.39, 0.37, 0.33, 0.26, 0.18, 0.09, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.03, 0.13, 0.19, 0.22, 0.24, 0.24, 0.23, 0.18, 0.13, 0.05, 0.0, 0.0, 0.0, 0.0],
+    [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.02, 0.06, 0.08, 0.09, 0.07, 0.05, 0.01, 0.0, 0.0, 0.0, 0.0, 0.0],
+]
+
+
+def kernel():
+    center = RADIUS - 1
+    values = {}
+    for y in range(2 * RADIUS):
+        for x in range(2 * RADIUS):
+            distance = math.hypot(x - center, y - center) / RADIUS
+            if distance != 0:
+                values[(x - center, y - center)] = math.exp(
+                    -(((distance - 0.5) / 0.15) ** 2) / 2
+                )
+    total = sum(values.values())
+    return {offset: value / total for offset, value in values.items()}
+
+
+def growth(potential):
+    return 2 * math.exp(-(((potential - 0.15) / 0.015) ** 2) / 2) - 1
+
+
+def evolve(state, kernel):
+    # Scatter the value of each non-empty cell to its neighbours
+    potential = [[0.0] * SIZE for _ in range(SIZE)]
+    for y in range(SIZE):
+        for x in range(SIZE):
+            value = state[y][x]
+            if value == 0:
+                continue
+            for (dx, dy), coefficient in kernel.items():
+                potential[(y + dy) % SIZE][(x + dx) % SIZE] += coefficient * value
+
+    return [
+        [
+            min(max(state[y][x] + TIME_STEP * growth(potential[y][x]), 0.0), 1.0)
+            for x in range(SIZE)
+        ]
+        for y in range(SIZE)
+    ]
+
+
+def circular_mean(masses):
+    angles = [2 * math.pi * position / SIZE for position in range(SIZE)]
+    sin_sum = sum(mass * math.sin(angle) for mass, angle in zip(masses, angles))
+    cos_sum = sum(mass * math.cos(angle) for mass, angle in zip(masses, angles))
+    mean_angle = math.atan2(sin_sum, cos_sum) % (2 * math.pi)
+    return mean_angle * SIZE / (2 * math.pi)
+
+
+def main():
+    state = [
+        [ORBIUM[y][x] if y < len(ORBIUM) and x < len(ORBIUM[y]) else 0.0 for x in range(SIZE)]
+        for y in range(SIZE)
+    ]
+    coefficients = kernel()
+
+    for step in range(STEPS[-1] + 1):
+        if step in STEPS:
+            mass = sum(map(sum, state))
+            centroid_x = circular_mean([sum(row[x] for row in state) for x in range(SIZE)])
+            centroid_y = circular_mean([sum(row) for row in state])
+            print(f"({step}, {mass!r}, {centroid_x!r}, {centroid_y!r}),")
+        state = evolve(state, coefficients)
+
+
+if __name__ == "__main__":
+    main()
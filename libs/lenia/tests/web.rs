@@ -0,0 +1,31 @@
+//! Test suite for the Web and headless browsers.
+#![cfg(target_arch = "wasm32")]
+
+extern crate lenia;
+extern crate wasm_bindgen_test;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+fn test_typed_arrays() {
+    let mut world = lenia::lenia();
+    world.evolve();
+
+    assert_eq!(world.state_array().length() as usize, world.state_len());
+    assert_eq!(
+        world.convoluted_state_array().length() as usize,
+        world.state_len()
+    );
+    assert_eq!(
+        world.convolution_kernel_array().length() as usize,
+        world.convolution_kernel_len()
+    );
+    // Copies are equal to the actual buffers
+    assert_eq!(world.state_array().to_vec(), world.get_state());
+    assert_eq!(
+        world.convoluted_state_array().to_vec(),
+        world.get_convoluted_state()
+    );
+}
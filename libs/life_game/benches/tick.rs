@@ -0,0 +1,24 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use life_game::Universe;
+
+fn bench_tick(c: &mut Criterion) {
+    let mut group = c.benchmark_group("tick");
+    group.sample_size(10);
+
+    [64, 512, 4096].iter().for_each(|&size| {
+        let mut universe = Universe::new(size, size);
+        universe.randomize(1, 0.3);
+        // Skip the first generations, where the whole soup changes
+        (0..10).for_each(|_| universe.tick());
+
+        group.throughput(Throughput::Elements((size * size) as u64));
+        group.bench_function(BenchmarkId::new("soup", format!("{}²", size)), |b| {
+            b.iter(|| universe.tick())
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_tick);
+criterion_main!(benches);
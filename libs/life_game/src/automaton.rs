@@ -0,0 +1,122 @@
+/// Cellular automaton on a toroidal grid
+///
+/// An automaton is defined by the states of its cells, the neighbourhood of
+/// a cell and the transition computing the next state of a cell from its
+/// neighbours. Implementations can override `next_generation` with a faster
+/// algorithm, as long as it gives the same result.
+pub trait Automaton {
+    /// State of a cell
+    type State: Copy;
+
+    fn width(&self) -> u32;
+
+    fn height(&self) -> u32;
+
+    /// States of the cells, row by row
+    fn grid(&self) -> &[Self::State];
+
+    /// Replace the states of the cells
+    fn set_grid(&mut self, grid: Vec<Self::State>);
+
+    /// Position of the neighbours of the cell at the specified position,
+    /// as (row, column) offsets, in the order they are passed to `transition`
+    fn neighbourhood(&self, row: u32, column: u32) -> Vec<(i32, i32)>;
+
+    /// Compute the next state of a cell from its current
+    /// state and the states of its neighbours
+    fn transition(&self, state: Self::State, neighbors: &[Self::State]) -> Self::State;
+
+    /// Calculate the next generation of cells, without
+    /// updating the automaton
+    fn next_generation(&self) -> Vec<Self::State> {
+        next_generation(self)
+    }
+
+    /// Move the automaton to its next generation
+    fn step(&mut self) {
+        let next_generation = self.next_generation();
+        self.set_grid(next_generation);
+    }
+}
+
+/// Calculate the next generation of an automaton by applying its
+/// transition to the neighbourhood of every cell
+pub fn next_generation<A: Automaton + ?Sized>(automaton: &A) -> Vec<A::State> {
+    let width = automaton.width() as i32;
+    let height = automaton.height() as i32;
+    let grid = automaton.grid();
+    let mut neighbors = Vec::new();
+
+    (0..height)
+        .flat_map(|row| (0..width).map(move |column| (row, column)))
+        .map(|(row, column)| {
+            let neighbourhood = automaton.neighbourhood(row as u32, column as u32);
+            neighbors.clear();
+            neighbors.extend(neighbourhood.iter().map(|(delta_row, delta_col)| {
+                let neighbor_row = (row + delta_row).rem_euclid(height);
+                let neighbor_col = (column + delta_col).rem_euclid(width);
+                grid[(neighbor_row * width + neighbor_col) as usize]
+            }));
+
+            automaton.transition(grid[(row * width + column) as usize], &neighbors)
+        })
+        .collect()
+}
+
+/// Offsets of the Moore neighbourhood, clockwise from the north
+pub const MOORE_NEIGHBOURHOOD: [(i32, i32); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// Offsets of the von Neumann neighbourhood, clockwise from the north
+pub const VON_NEUMANN_NEIGHBOURHOOD: [(i32, i32); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Generations, Universe};
+
+    #[test]
+    fn test_universe_next_generation() {
+        // The fast algorithms of the universe give the
+        // same result as the generic one
+        let rules = [
+            "R1,C0,M0,S2..3,B3..3,NM",
+            "R5,C0,M1,S34..58,B34..45,NM",
+            "R3,C0,M0,S5..9,B4..6,NN",
+            "R2,C0,M0,S3..4,B2..3,NH",
+            "R1,C0,M0,S2..3,B4..4,NT",
+            "R2,C0,M1,S6..11,B7..9,NT",
+        ];
+
+        rules.iter().for_each(|rule| {
+            let mut universe = Universe::new(24, 18);
+            universe.set_rule(rule).unwrap();
+            universe.randomize(3, 0.35);
+
+            assert_eq!(
+                Automaton::next_generation(&universe),
+                next_generation(&universe),
+                "{}",
+                rule
+            );
+        });
+    }
+
+    #[test]
+    fn test_step() {
+        let mut generations = Generations::new(8, 8);
+        generations.set_cells_alive(vec![(3, 3), (3, 4)]);
+
+        let expected = next_generation(&generations);
+        generations.step();
+        assert_eq!(generations.grid(), expected.as_slice());
+    }
+}
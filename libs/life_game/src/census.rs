@@ -0,0 +1,337 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    patterns::{Pattern, PATTERNS},
+    Automaton, Cell, Universe,
+};
+
+/// Name given to the objects that are not in the catalogue
+pub const UNKNOWN_OBJECT: &str = "Unknown";
+
+/// Longest period searched when building the catalogue
+const CATALOGUE_MAX_PERIOD: u32 = 32;
+
+/// Size of the universe used to compute the phases of the library patterns
+const CATALOGUE_UNIVERSE_SIZE: u32 = 64;
+
+/// Cells of an object, as (row, column) coordinates relative to
+/// the object. Canonical shapes do not depend on the position or the
+/// orientation of the object.
+pub type Shape = Vec<(i32, i32)>;
+
+/// Rotation or reflection of a cell position
+type Transformation = fn(i32, i32) -> (i32, i32);
+
+/// Count of the objects found in a universe, by name
+#[wasm_bindgen]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Census {
+    counts: BTreeMap<String, u32>,
+}
+
+#[wasm_bindgen]
+impl Census {
+    /// Names of the objects found, in alphabetical order
+    pub fn names(&self) -> Vec<String> {
+        self.counts.keys().cloned().collect()
+    }
+
+    /// Number of objects found for each name, in the order of `names`
+    pub fn counts(&self) -> Vec<u32> {
+        self.counts.values().cloned().collect()
+    }
+
+    /// Number of objects found with the specified name
+    pub fn count(&self, name: &str) -> u32 {
+        *self.counts.get(name).unwrap_or(&0)
+    }
+
+    /// Total number of objects found
+    pub fn total(&self) -> u32 {
+        self.counts.values().sum()
+    }
+}
+
+/// Known objects, indexed by the canonical shapes of all their phases
+#[derive(Debug, Clone)]
+pub struct Catalogue {
+    separation: u32,
+    shapes: HashMap<Shape, &'static str>,
+}
+
+impl Catalogue {
+    /// Build a catalogue from the periodic patterns of the pattern library
+    ///
+    /// Every phase of still lifes, oscillators and spaceships is registered,
+    /// as long as it is a single object for the specified `separation`.
+    /// Patterns that do not come back to their initial shape (methuselahs,
+    /// guns...) are ignored.
+    pub fn from_library(separation: u32) -> Self {
+        let mut shapes = HashMap::new();
+
+        PATTERNS
+            .iter()
+            .filter_map(|pattern| phases(pattern, separation).map(|phases| (pattern.name, phases)))
+            .for_each(|(name, phases)| {
+                phases.into_iter().for_each(|shape| {
+                    shapes.entry(shape).or_insert(name);
+                })
+            });
+
+        Self { separation, shapes }
+    }
+
+    /// Largest distance between two alive cells of the same object
+    pub fn separation(&self) -> u32 {
+        self.separation
+    }
+
+    /// Name of the object with the specified canonical shape, if known
+    pub fn name(&self, shape: &Shape) -> Option<&'static str> {
+        self.shapes.get(shape).copied()
+    }
+
+    /// Split the alive cells of a universe into objects, and count them by name
+    pub fn census(&self, universe: &Universe) -> Census {
+        let mut census = Census::default();
+
+        components(
+            &universe.cells,
+            universe.width,
+            universe.height,
+            self.separation,
+        )
+        .iter()
+        .map(|component| {
+            self.name(&canonical_shape(component))
+                .unwrap_or(UNKNOWN_OBJECT)
+        })
+        .for_each(|name| *census.counts.entry(name.to_string()).or_insert(0) += 1);
+
+        census
+    }
+}
+
+/// Compute the canonical shapes of every phase of a periodic pattern
+///
+/// Return `None` if the pattern does not come back to its initial shape
+/// within `CATALOGUE_MAX_PERIOD` generations, or if one of its phases is
+/// not a single object.
+fn phases(pattern: &Pattern, separation: u32) -> Option<Vec<Shape>> {
+    let mut universe = Universe::new(CATALOGUE_UNIVERSE_SIZE, CATALOGUE_UNIVERSE_SIZE);
+    universe.init(pattern.pattern.to_string());
+
+    let initial_hash = universe.signature().hash;
+    let mut phases = Vec::new();
+
+    for _ in 0..CATALOGUE_MAX_PERIOD {
+        let objects = components(&universe.cells, universe.width, universe.height, separation);
+        if objects.len() != 1 {
+            return None;
+        }
+        phases.push(canonical_shape(&objects[0]));
+
+        universe.cells = universe.next_generation();
+
+        if universe.signature().hash == initial_hash {
+            return Some(phases);
+        }
+    }
+
+    None
+}
+
+/// Split the alive cells of a toroidal universe into connected components
+///
+/// Two alive cells belong to the same component if they are at most
+/// `separation` cells away on both axes (a separation of 1 is the usual
+/// 8-neighbourhood). Coordinates of a component are unwrapped: a component
+/// crossing the universe edges is returned in one piece.
+pub fn components(cells: &[Cell], width: u32, height: u32, separation: u32) -> Vec<Shape> {
+    let (width, height) = (width as i32, height as i32);
+    let separation = separation as i32;
+    let mut visited = vec![false; cells.len()];
+    let mut components = Vec::new();
+
+    let index_of = |row: i32, column: i32| {
+        (row.rem_euclid(height) * width + column.rem_euclid(width)) as usize
+    };
+
+    for start in 0..cells.len() {
+        if visited[start] || cells[start] == Cell::Dead {
+            continue;
+        }
+
+        let mut component = Vec::new();
+        let mut queue = VecDeque::from([(start as i32 / width, start as i32 % width)]);
+        visited[start] = true;
+
+        while let Some((row, column)) = queue.pop_front() {
+            component.push((row, column));
+
+            for delta_row in -separation..=separation {
+                for delta_col in -separation..=separation {
+                    let neighbor = (row + delta_row, column + delta_col);
+                    let index = index_of(neighbor.0, neighbor.1);
+
+                    if !visited[index] && cells[index] == Cell::Alive {
+                        visited[index] = true;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+
+        components.push(component);
+    }
+
+    components
+}
+
+/// Compute the canonical shape of a set of cells
+///
+/// The cells are transformed by the 8 rotations and reflections of the
+/// square, each one is translated to the origin and sorted: the smallest
+/// result is the canonical shape.
+pub fn canonical_shape(cells: &[(i32, i32)]) -> Shape {
+    let transformations: [Transformation; 8] = [
+        |r, c| (r, c),
+        |r, c| (c, -r),
+        |r, c| (-r, -c),
+        |r, c| (-c, r),
+        |r, c| (r, -c),
+        |r, c| (-r, c),
+        |r, c| (c, r),
+        |r, c| (-c, -r),
+    ];
+
+    transformations
+        .iter()
+        .map(|transformation| {
+            let mut shape: Shape = cells.iter().map(|&(r, c)| transformation(r, c)).collect();
+            let min_row = shape.iter().map(|&(r, _)| r).min().unwrap_or(0);
+            let min_col = shape.iter().map(|&(_, c)| c).min().unwrap_or(0);
+
+            shape
+                .iter_mut()
+                .for_each(|(r, c)| (*r, *c) = (*r - min_row, *c - min_col));
+            shape.sort_unstable();
+            shape
+        })
+        .min()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_components() {
+        let mut universe = Universe::new(10, 10);
+        // Block crossing the universe corner and a diagonal pair
+        universe.set_cells_alive(vec![(9, 9), (9, 0), (0, 9), (0, 0), (4, 4), (5, 5)]);
+
+        let components = components(&universe.cells, 10, 10, 1);
+        assert_eq!(components.len(), 2);
+        assert_eq!(
+            canonical_shape(&components[0]),
+            vec![(0, 0), (0, 1), (1, 0), (1, 1)]
+        );
+
+        // The diagonal pair merges with nothing else, even with a larger separation
+        assert_eq!(super::components(&universe.cells, 10, 10, 3).len(), 2);
+        assert_eq!(super::components(&universe.cells, 10, 10, 4).len(), 1);
+    }
+
+    #[test]
+    fn test_canonical_shape() {
+        let horizontal = canonical_shape(&[(3, 3), (3, 4), (3, 5)]);
+        let vertical = canonical_shape(&[(7, 1), (8, 1), (9, 1)]);
+        assert_eq!(horizontal, vertical);
+
+        // All the orientations of a glider have the same shape
+        let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+        let mirrored: Vec<(i32, i32)> = glider.iter().map(|&(r, c)| (r, -c)).collect();
+        let rotated: Vec<(i32, i32)> = glider.iter().map(|&(r, c)| (c, -r)).collect();
+        assert_eq!(canonical_shape(&glider), canonical_shape(&mirrored));
+        assert_eq!(canonical_shape(&glider), canonical_shape(&rotated));
+    }
+
+    #[test]
+    fn test_catalogue() {
+        let catalogue = Catalogue::from_library(1);
+        let name = |cells: &[(i32, i32)]| catalogue.name(&canonical_shape(cells));
+
+        assert_eq!(name(&[(0, 0), (0, 1), (1, 0), (1, 1)]), Some("Block"));
+        assert_eq!(name(&[(0, 0), (1, 0), (2, 0)]), Some("Blinker"));
+        assert_eq!(
+            name(&[(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]),
+            Some("Glide spaceship")
+        );
+        // The R-pentomino is not periodic
+        assert_eq!(name(&[(0, 1), (0, 2), (1, 0), (1, 1), (2, 1)]), None);
+        // The pulsar is made of 4 objects for a separation of 1...
+        assert!(catalogue.shapes.values().all(|name| *name != "Pulsar"));
+        // ...but of one for a separation of 2
+        assert!(Catalogue::from_library(2)
+            .shapes
+            .values()
+            .any(|name| *name == "Pulsar"));
+    }
+
+    #[test]
+    fn test_census() {
+        let mut universe = Universe::new(32, 32);
+        universe.set_cells_alive(vec![
+            // Two blocks
+            (1, 1),
+            (1, 2),
+            (2, 1),
+            (2, 2),
+            (10, 20),
+            (10, 21),
+            (11, 20),
+            (11, 21),
+            // A vertical blinker
+            (20, 5),
+            (21, 5),
+            (22, 5),
+            // A mirrored glider
+            (20, 21),
+            (21, 20),
+            (22, 20),
+            (22, 21),
+            (22, 22),
+            // A lonely cell
+            (28, 28),
+        ]);
+
+        let census = universe.census(1);
+        assert_eq!(census.count("Block"), 2);
+        assert_eq!(census.count("Blinker"), 1);
+        assert_eq!(census.count("Glide spaceship"), 1);
+        assert_eq!(census.count(UNKNOWN_OBJECT), 1);
+        assert_eq!(census.total(), 5);
+        assert_eq!(
+            census.names(),
+            vec!["Blinker", "Block", "Glide spaceship", "Unknown"]
+        );
+        assert_eq!(census.counts(), vec![1, 2, 1, 1]);
+
+        // The catalogue is only rebuilt for another separation
+        let separation = |universe: &Universe| {
+            universe
+                .catalogue
+                .borrow()
+                .as_ref()
+                .map(Catalogue::separation)
+        };
+        assert_eq!(universe.census(1), census);
+        assert_eq!(separation(&universe), Some(1));
+        universe.census(3);
+        assert_eq!(separation(&universe), Some(3));
+    }
+}
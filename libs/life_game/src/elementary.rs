@@ -0,0 +1,323 @@
+use js_sys::Uint8Array;
+use maths::{matrix::Matrix, random::Random};
+use wasm_bindgen::prelude::*;
+
+use crate::utils::set_panic_hook;
+
+/// Behaviour of a one dimension automaton at its edges
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Boundary {
+    /// The row wraps around, its edges are neighbours
+    Periodic = 0,
+    /// Cells beyond the edges are always dead
+    Fixed = 1,
+}
+
+/// Rule of a one dimension automaton with two states
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementaryRule {
+    /// Rule numbered by Wolfram (0 to 255): the bit `4 * left + 2 * center
+    /// + right` of the number is the next state of a cell
+    Wolfram(u8),
+    /// Totalistic rule: the bit `n` of the code is the next state of a cell
+    /// when `n` cells are alive at most `radius` cells away (the cell
+    /// included), so the radius can be at most 15
+    Totalistic { radius: u32, code: u32 },
+}
+
+impl ElementaryRule {
+    /// Number of cells read on each side of a cell
+    pub fn radius(&self) -> u32 {
+        match self {
+            ElementaryRule::Wolfram(_) => 1,
+            ElementaryRule::Totalistic { radius, .. } => *radius,
+        }
+    }
+
+    /// Compute the next state of a cell from the states of its
+    /// neighbourhood, from left to right
+    pub fn next_state(&self, neighbourhood: impl Iterator<Item = u8>) -> u8 {
+        match self {
+            ElementaryRule::Wolfram(number) => {
+                let index = neighbourhood.fold(0, |index, cell| index << 1 | cell);
+                number >> index & 1
+            }
+            ElementaryRule::Totalistic { code, .. } => {
+                let alive: u32 = neighbourhood.map(u32::from).sum();
+                (code >> alive & 1) as u8
+            }
+        }
+    }
+}
+
+/// One dimension cellular automaton with two states
+///
+/// Every generation is remembered, so that the space-time diagram of the
+/// automaton can be rendered, one row per generation.
+#[wasm_bindgen]
+pub struct Elementary {
+    width: u32,
+    rule: ElementaryRule,
+    boundary: Boundary,
+    cells: Vec<u8>,
+    space_time: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Elementary {
+    /// Instantiate a new empty automaton following the
+    /// specified Wolfram rule
+    pub fn new(width: u32, rule: u8, boundary: Boundary) -> Result<Elementary, String> {
+        Self::with_rule(width, ElementaryRule::Wolfram(rule), boundary)
+    }
+
+    /// Instantiate a new empty automaton following a totalistic rule
+    pub fn totalistic(
+        width: u32,
+        radius: u32,
+        code: u32,
+        boundary: Boundary,
+    ) -> Result<Elementary, String> {
+        Self::with_rule(width, ElementaryRule::Totalistic { radius, code }, boundary)
+    }
+
+    /// Init the automaton with a single alive cell in its middle
+    pub fn single_seed(&mut self) {
+        self.cells.fill(0);
+        self.cells[self.width as usize / 2] = 1;
+        self.reset_space_time();
+    }
+
+    /// Init the automaton with random cells
+    ///
+    /// Each cell is alive with the probability `density`.
+    /// The same seed always generates the same row.
+    pub fn randomize(&mut self, seed: u32, density: f64) {
+        let mut random = Random::new(seed as u64);
+
+        self.cells
+            .iter_mut()
+            .for_each(|cell| *cell = random.next_bool(density) as u8);
+        self.reset_space_time();
+    }
+
+    /// Move the automaton to its next generation
+    pub fn tick(&mut self) {
+        let radius = self.rule.radius() as i64;
+        let width = self.width as i64;
+
+        self.cells = (0..width)
+            .map(|column| {
+                let neighbourhood =
+                    (column - radius..=column + radius).map(|neighbor| match self.boundary {
+                        Boundary::Periodic => self.cells[neighbor.rem_euclid(width) as usize],
+                        Boundary::Fixed if neighbor < 0 || neighbor >= width => 0,
+                        Boundary::Fixed => self.cells[neighbor as usize],
+                    });
+
+                self.rule.next_state(neighbourhood)
+            })
+            .collect();
+
+        self.space_time.extend_from_slice(&self.cells);
+    }
+
+    /// Compute the specified number of generations
+    pub fn run(&mut self, generations: u32) {
+        (0..generations).for_each(|_| self.tick());
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Number of generations computed since the initialisation
+    pub fn generation(&self) -> u32 {
+        (self.space_time.len() / self.width as usize) as u32 - 1
+    }
+
+    /// Pointer to the states of the cells of the current generation
+    pub fn cells(&self) -> *const u8 {
+        self.cells.as_ptr()
+    }
+
+    /// Number of cells behind the `cells` pointer
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Copy of the states of the cells of the current generation
+    pub fn cells_array(&self) -> Uint8Array {
+        Uint8Array::from(self.cells.as_slice())
+    }
+
+    /// States of the cells of every generation, row by row
+    pub fn render_space_time(&self) -> Vec<u8> {
+        self.space_time.clone()
+    }
+
+    /// Forget the previous generations
+    fn reset_space_time(&mut self) {
+        self.space_time = self.cells.clone();
+    }
+}
+
+impl Elementary {
+    /// Instantiate a new empty automaton following the specified rule
+    ///
+    /// Unlike `new`, it accepts any `ElementaryRule`, like a
+    /// totalistic one.
+    pub fn with_rule(
+        width: u32,
+        rule: ElementaryRule,
+        boundary: Boundary,
+    ) -> Result<Elementary, String> {
+        // Install panic hook
+        set_panic_hook();
+
+        if width == 0 {
+            return Err("Automaton width can not be null".to_string());
+        }
+        if rule.radius() > 15 {
+            return Err(format!(
+                "Totalistic rule radius is too large: {}",
+                rule.radius()
+            ));
+        }
+
+        Ok(Elementary {
+            width,
+            rule,
+            boundary,
+            cells: vec![0; width as usize],
+            space_time: vec![0; width as usize],
+        })
+    }
+
+    /// Space-time diagram of the automaton: one row per generation,
+    /// the initial one first
+    pub fn space_time(&self) -> Matrix<u8> {
+        Matrix::from_vec(
+            self.space_time.clone(),
+            self.width as usize,
+            self.generation() as usize + 1,
+        )
+        .unwrap()
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl Elementary {
+    /// Get the states of the cells of the current generation
+    pub fn get_cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// Set cells to be alive by passing their columns
+    pub fn set_cells_alive(&mut self, columns: Vec<u32>) {
+        columns
+            .into_iter()
+            .for_each(|column| self.cells[column as usize] = 1);
+        self.reset_space_time();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rule_30() {
+        let mut automaton = Elementary::new(11, 30, Boundary::Periodic).unwrap();
+        automaton.single_seed();
+        automaton.run(3);
+
+        let expected: Vec<u8> = ["00000100000", "00001110000", "00011001000", "00110111100"]
+            .iter()
+            .flat_map(|row| row.bytes().map(|byte| byte - b'0'))
+            .collect();
+
+        assert_eq!(automaton.generation(), 3);
+        assert_eq!(
+            automaton.space_time(),
+            Matrix::from_vec(expected, 11, 4).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rule_90() {
+        // Rule 90 draws the Pascal's triangle modulo 2
+        let mut automaton = Elementary::new(64, 90, Boundary::Fixed).unwrap();
+        automaton.single_seed();
+        automaton.run(16);
+
+        let space_time = automaton.space_time();
+        let mut binomials = vec![1u32];
+
+        for generation in 0..=16 {
+            (0..=generation).for_each(|k| {
+                let column = 32 - generation + 2 * k;
+                assert_eq!(
+                    space_time.m[generation * 64 + column] as u32,
+                    binomials[k] % 2
+                );
+            });
+
+            binomials = (0..=generation + 1)
+                .map(|k| {
+                    let left = if k > 0 { binomials[k - 1] } else { 0 };
+                    let right = *binomials.get(k).unwrap_or(&0);
+                    left + right
+                })
+                .collect();
+        }
+
+        assert_eq!(
+            space_time.m.iter().map(|&cell| cell as u32).sum::<u32>(),
+            81 + 2
+        );
+    }
+
+    #[test]
+    fn test_boundaries() {
+        // Rule 170 shifts the cells to the left
+        let mut periodic = Elementary::new(5, 170, Boundary::Periodic).unwrap();
+        periodic.set_cells_alive(vec![0]);
+        periodic.tick();
+        assert_eq!(periodic.get_cells(), &[0, 0, 0, 0, 1]);
+
+        let mut fixed = Elementary::new(5, 170, Boundary::Fixed).unwrap();
+        fixed.set_cells_alive(vec![0]);
+        fixed.tick();
+        assert_eq!(fixed.get_cells(), &[0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_totalistic() {
+        // A totalistic rule of radius 1 is a symmetric Wolfram rule
+        let mut totalistic = Elementary::totalistic(40, 1, 0b0110, Boundary::Periodic).unwrap();
+        let mut wolfram = Elementary::new(40, 126, Boundary::Periodic).unwrap();
+        totalistic.randomize(5, 0.5);
+        wolfram.randomize(5, 0.5);
+        totalistic.run(20);
+        wolfram.run(20);
+        assert_eq!(totalistic.space_time(), wolfram.space_time());
+
+        // Larger radius: born or surviving with exactly 2 alive cells among 5
+        let mut totalistic = Elementary::totalistic(9, 2, 0b100, Boundary::Fixed).unwrap();
+        totalistic.set_cells_alive(vec![3, 4]);
+        totalistic.tick();
+        assert_eq!(totalistic.get_cells(), &[0, 0, 1, 1, 1, 1, 0, 0, 0]);
+
+        assert!(Elementary::totalistic(9, 16, 0, Boundary::Fixed).is_err());
+    }
+
+    #[test]
+    fn test_null_width() {
+        assert!(Elementary::new(0, 30, Boundary::Periodic).is_err());
+        assert!(Elementary::totalistic(0, 1, 0b0110, Boundary::Fixed).is_err());
+        assert!(Elementary::with_rule(0, ElementaryRule::Wolfram(90), Boundary::Fixed).is_err());
+    }
+}
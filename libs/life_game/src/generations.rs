@@ -0,0 +1,379 @@
+use std::fmt;
+use std::str::FromStr;
+
+use maths::random::Random;
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    automaton::{Automaton, MOORE_NEIGHBOURHOOD},
+    utils::set_panic_hook,
+};
+
+/// State of a dead cell in a Generations universe
+pub const DEAD: u8 = 0;
+
+/// State of an alive cell in a Generations universe, the only
+/// state counted as a neighbour
+pub const ALIVE: u8 = 1;
+
+/// Generations rule
+///
+/// Alive cells that do not survive do not die immediately: they go
+/// through the refractory states `2..states`, one per generation, before
+/// dying. Refractory cells are not counted as neighbours and can not be
+/// born again until they are dead.
+///
+/// Rules are written in the `survival/birth/states` notation, for instance
+/// Brian's Brain is `/2/3` and Star Wars is `345/2/4`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GenerationsRule {
+    pub survival: [bool; 9],
+    pub birth: [bool; 9],
+    pub states: u8,
+}
+
+impl GenerationsRule {
+    /// Brian's Brain: every alive cell goes through one refractory state
+    pub fn brians_brain() -> Self {
+        "/2/3".parse().unwrap()
+    }
+
+    /// Star Wars: alive cells go through two refractory states
+    pub fn star_wars() -> Self {
+        "345/2/4".parse().unwrap()
+    }
+
+    /// Compute the next state of a cell from its current
+    /// state and its number of alive neighbours
+    pub fn next_state(&self, state: u8, alive_neighbors: u8) -> u8 {
+        let alive_neighbors = alive_neighbors as usize;
+
+        match state {
+            DEAD if self.birth[alive_neighbors] => ALIVE,
+            DEAD => DEAD,
+            ALIVE if self.survival[alive_neighbors] => ALIVE,
+            // The last refractory state is followed by death
+            state => (state + 1) % self.states,
+        }
+    }
+}
+
+impl Default for GenerationsRule {
+    fn default() -> Self {
+        Self::brians_brain()
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let [survival, birth, states] = input.trim().split('/').collect::<Vec<_>>()[..] else {
+            return Err(format!("Invalid Generations rule: {}", input));
+        };
+
+        let states = states
+            .parse()
+            .map_err(|_| format!("Invalid number of states: {}", states))?;
+        if states < 2 {
+            return Err(format!("A rule needs at least 2 states: {}", input));
+        }
+
+        Ok(Self {
+            survival: parse_counts(survival)?,
+            birth: parse_counts(birth)?,
+            states,
+        })
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts = |counts: &[bool; 9]| {
+            (0..9)
+                .filter(|&count| counts[count])
+                .map(|count| count.to_string())
+                .collect::<String>()
+        };
+
+        write!(
+            f,
+            "{}/{}/{}",
+            counts(&self.survival),
+            counts(&self.birth),
+            self.states
+        )
+    }
+}
+
+/// Parse a list of neighbour counts written as digits, like `345`
+fn parse_counts(input: &str) -> Result<[bool; 9], String> {
+    let mut counts = [false; 9];
+
+    for c in input.chars() {
+        match c.to_digit(10) {
+            Some(count) if count < 9 => counts[count as usize] = true,
+            _ => return Err(format!("Invalid neighbour count: {}", c)),
+        }
+    }
+
+    Ok(counts)
+}
+
+/// Universe of a Generations rule
+///
+/// Cells are stored as one byte per cell: `DEAD`, `ALIVE`, or the index
+/// of their refractory state, so that they can be coloured by state.
+#[wasm_bindgen]
+pub struct Generations {
+    tick_count: u32,
+    width: u32,
+    height: u32,
+    rule: GenerationsRule,
+    states: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl Generations {
+    /// Instantiate a new empty universe following Brian's Brain rule
+    pub fn new(width: u32, height: u32) -> Generations {
+        // Install panic hook
+        set_panic_hook();
+
+        Generations {
+            tick_count: 0,
+            width,
+            height,
+            rule: GenerationsRule::default(),
+            states: vec![DEAD; (width * height) as usize],
+        }
+    }
+
+    /// Set the rule of the universe, written in the `survival/birth/states`
+    /// notation (for instance `345/2/4`)
+    ///
+    /// Cells in a state that does not exist in the new rule die.
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), String> {
+        self.rule = rule.parse()?;
+
+        let states = self.rule.states;
+        self.states
+            .iter_mut()
+            .filter(|state| **state >= states)
+            .for_each(|state| *state = DEAD);
+
+        Ok(())
+    }
+
+    /// Rule of the universe, in the `survival/birth/states` notation
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Number of states of the rule, dead and alive included
+    pub fn state_count(&self) -> u8 {
+        self.rule.states
+    }
+
+    /// Init universe with a random soup
+    ///
+    /// Each cell is alive with the probability `density`.
+    /// The same seed always generates the same universe.
+    pub fn randomize(&mut self, seed: u32, density: f64) {
+        let mut random = Random::new(seed as u64);
+
+        self.states.iter_mut().for_each(|state| {
+            *state = if random.next_bool(density) {
+                ALIVE
+            } else {
+                DEAD
+            }
+        });
+    }
+
+    /// Move the universe to its next state
+    pub fn tick(&mut self) {
+        self.tick_count += 1;
+        self.step();
+    }
+
+    /// Toggle a cell between alive and dead, refractory cells die
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        let idx = self.get_cell_index(row, column);
+        self.states[idx] = if self.states[idx] == DEAD {
+            ALIVE
+        } else {
+            DEAD
+        };
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Pointer to the states of the cells, one byte per cell
+    pub fn states(&self) -> *const u8 {
+        self.states.as_ptr()
+    }
+
+    /// Number of cells behind the `states` pointer
+    pub fn states_len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Copy of the states of the cells, one byte per cell
+    pub fn states_array(&self) -> Uint8Array {
+        Uint8Array::from(self.states.as_slice())
+    }
+
+    /// Number of generations computed since the universe creation
+    pub fn generation(&self) -> u32 {
+        self.tick_count
+    }
+
+    /// Number of alive cells in the universe, refractory cells excluded
+    pub fn population(&self) -> u32 {
+        self.states.iter().filter(|&&state| state == ALIVE).count() as u32
+    }
+
+    /// Given the position of a cell in the universe, return
+    /// its index in the storage vector
+    fn get_cell_index(&self, row: u32, column: u32) -> usize {
+        ((row * self.width) + column) as usize
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl Generations {
+    /// Get the states of the entire universe
+    pub fn get_states(&self) -> &[u8] {
+        &self.states
+    }
+
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell as an array.
+    pub fn set_cells_alive(&mut self, cells: Vec<(u32, u32)>) {
+        for (row, column) in cells {
+            let cell_index = self.get_cell_index(row, column);
+            self.states[cell_index] = ALIVE;
+        }
+    }
+}
+
+impl Automaton for Generations {
+    type State = u8;
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn grid(&self) -> &[u8] {
+        &self.states
+    }
+
+    fn set_grid(&mut self, grid: Vec<u8>) {
+        self.states = grid;
+    }
+
+    fn neighbourhood(&self, _row: u32, _column: u32) -> Vec<(i32, i32)> {
+        MOORE_NEIGHBOURHOOD.to_vec()
+    }
+
+    fn transition(&self, state: u8, neighbors: &[u8]) -> u8 {
+        let alive_neighbors = neighbors.iter().filter(|&&state| state == ALIVE).count();
+        self.rule.next_state(state, alive_neighbors as u8)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let star_wars = GenerationsRule::star_wars();
+        assert_eq!(star_wars.states, 4);
+        assert!(star_wars.survival[3] && star_wars.survival[4] && star_wars.survival[5]);
+        assert!(!star_wars.survival[2]);
+        assert!(star_wars.birth[2] && !star_wars.birth[3]);
+
+        assert_eq!(
+            "23/3/2".parse::<GenerationsRule>().unwrap().to_string(),
+            "23/3/2"
+        );
+        assert_eq!(GenerationsRule::brians_brain().to_string(), "/2/3");
+
+        assert!("23/3".parse::<GenerationsRule>().is_err());
+        assert!("23/3/1".parse::<GenerationsRule>().is_err());
+        assert!("29/3/3".parse::<GenerationsRule>().is_err());
+        assert!("2x/3/3".parse::<GenerationsRule>().is_err());
+    }
+
+    #[test]
+    fn test_refractory_cycle() {
+        let rule = GenerationsRule::star_wars();
+
+        // An alive cell that does not survive goes through
+        // states 2 and 3 whatever its neighbours, then dies
+        assert_eq!(rule.next_state(ALIVE, 4), ALIVE);
+        assert_eq!(rule.next_state(ALIVE, 2), 2);
+        assert_eq!(rule.next_state(2, 4), 3);
+        assert_eq!(rule.next_state(3, 2), DEAD);
+        // Only dead cells can be born
+        assert_eq!(rule.next_state(DEAD, 2), ALIVE);
+    }
+
+    #[test]
+    fn test_brians_brain() {
+        let mut universe = Generations::new(8, 8);
+        universe.set_cells_alive(vec![(3, 3), (3, 4)]);
+
+        universe.tick();
+        let states = universe.get_states();
+        // Alive cells never survive in Brian's Brain
+        assert_eq!(states[3 * 8 + 3], 2);
+        assert_eq!(states[3 * 8 + 4], 2);
+        // Dead cells with exactly 2 alive neighbours are born
+        for column in 3..5 {
+            assert_eq!(states[2 * 8 + column], ALIVE);
+            assert_eq!(states[4 * 8 + column], ALIVE);
+        }
+        assert_eq!(universe.population(), 4);
+
+        universe.tick();
+        let states = universe.get_states();
+        // The refractory cells died, without being born again
+        // although they had 4 alive neighbours
+        assert_eq!(states[3 * 8 + 3], DEAD);
+        assert_eq!(states[3 * 8 + 4], DEAD);
+        assert_eq!(states[2 * 8 + 3], 2);
+        assert_eq!(universe.generation(), 2);
+    }
+
+    #[test]
+    fn test_set_rule() {
+        let mut universe = Generations::new(4, 4);
+        universe.set_rule("345/2/4").unwrap();
+        universe.set_cells_alive(vec![(0, 0)]);
+        universe.tick();
+        universe.tick();
+        assert_eq!(universe.get_states()[0], 3);
+
+        // State 3 does not exist in Brian's Brain
+        universe.set_rule("/2/3").unwrap();
+        assert_eq!(universe.get_states()[0], DEAD);
+        assert_eq!(universe.state_count(), 3);
+
+        assert!(universe.set_rule("/2").is_err());
+        assert_eq!(universe.rule(), "/2/3");
+    }
+}
@@ -0,0 +1,8 @@
+/// Default memory budget of the history (in bytes)
+pub const DEFAULT_HISTORY_BUDGET: usize = 1024 * 1024;
+
+/// Bounded history of the generations of a universe
+///
+/// Each generation is stored as a delta: the indexes of the cells toggled
+/// to reach it from the previous generation.
+pub type History = maths::history::History<Vec<u32>>;
@@ -0,0 +1,480 @@
+use std::fmt;
+use std::str::FromStr;
+
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    automaton::{Automaton, MOORE_NEIGHBOURHOOD},
+    utils::{cell_bytes, set_panic_hook},
+    Cell,
+};
+
+/// Letters of the configurations of each neighbour count,
+/// in the Hensel notation
+const LETTERS: [&str; 9] = [
+    "",
+    "ce",
+    "aceikn",
+    "aceijknqry",
+    "aceijknqrtwyz",
+    "aceijknqry",
+    "aceikn",
+    "ce",
+    "",
+];
+
+/// A configuration of each letter of the Hensel notation, for neighbour
+/// counts up to 4 (the configurations of the larger counts are their
+/// complements). Neighbours are numbered clockwise from the north, like
+/// `MOORE_NEIGHBOURHOOD`.
+const CONFIGURATIONS: [(usize, char, &[usize]); 32] = [
+    (1, 'c', &[1]),
+    (1, 'e', &[0]),
+    (2, 'a', &[0, 1]),
+    (2, 'c', &[1, 3]),
+    (2, 'e', &[0, 2]),
+    (2, 'i', &[0, 4]),
+    (2, 'k', &[0, 3]),
+    (2, 'n', &[1, 5]),
+    (3, 'a', &[0, 1, 2]),
+    (3, 'c', &[1, 3, 5]),
+    (3, 'e', &[0, 2, 4]),
+    (3, 'i', &[7, 0, 1]),
+    (3, 'j', &[0, 1, 6]),
+    (3, 'k', &[0, 2, 5]),
+    (3, 'n', &[0, 1, 3]),
+    (3, 'q', &[0, 1, 5]),
+    (3, 'r', &[0, 1, 4]),
+    (3, 'y', &[0, 3, 5]),
+    (4, 'a', &[0, 1, 2, 3]),
+    (4, 'c', &[1, 3, 5, 7]),
+    (4, 'e', &[0, 2, 4, 6]),
+    (4, 'i', &[0, 1, 3, 4]),
+    (4, 'j', &[0, 1, 4, 6]),
+    (4, 'k', &[0, 1, 3, 6]),
+    (4, 'n', &[0, 1, 3, 7]),
+    (4, 'q', &[0, 1, 2, 5]),
+    (4, 'r', &[0, 1, 2, 4]),
+    (4, 't', &[7, 0, 1, 4]),
+    (4, 'w', &[0, 1, 5, 6]),
+    (4, 'y', &[0, 1, 3, 5]),
+    (4, 'z', &[0, 1, 4, 5]),
+    (0, ' ', &[]),
+];
+
+/// Compute the neighbour count and the Hensel letter of every
+/// configuration of the neighbourhood, indexed by its bit mask
+fn classify() -> [(usize, char); 256] {
+    let mut classes = [(0, ' '); 256];
+
+    for (count, letter, neighbors) in CONFIGURATIONS {
+        let mask = neighbors
+            .iter()
+            .fold(0, |mask, neighbor| mask | 1 << neighbor);
+
+        // The 8 rotations and reflections of the configuration, and of its
+        // complement for the larger counts (the complements of the
+        // configurations of 4 neighbours are configurations of 4 neighbours)
+        for quarter in 0..4 {
+            for reflection in [false, true] {
+                let transformed = (0..8)
+                    .filter(|neighbor| mask & 1 << neighbor != 0)
+                    .map(|neighbor: usize| {
+                        let neighbor = if reflection {
+                            (8 - neighbor) % 8
+                        } else {
+                            neighbor
+                        };
+                        (neighbor + 2 * quarter) % 8
+                    })
+                    .fold(0, |mask, neighbor| mask | 1 << neighbor);
+
+                classes[transformed] = (count, letter);
+                if count < 4 {
+                    classes[255 - transformed] = (8 - count, letter);
+                }
+            }
+        }
+    }
+
+    classes
+}
+
+/// Isotropic non-totalistic rule
+///
+/// Like the Life-like rules, a cell is born or survives depending on the
+/// number of its alive neighbours, but also on their configuration. Rules
+/// are written in the Hensel notation: each count can be followed by the
+/// letters of the configurations it applies to, or by a `-` and the letters
+/// of the configurations it excludes. For instance, `B2-a/S12`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IsotropicRule {
+    /// Indexed by the bit mask of the alive neighbours
+    birth: [bool; 256],
+    /// Indexed by the bit mask of the alive neighbours
+    survival: [bool; 256],
+}
+
+impl IsotropicRule {
+    /// Rule of the Conway's game of life
+    pub fn conway() -> Self {
+        "B3/S23".parse().unwrap()
+    }
+
+    /// Compute the next state of a cell from its current state and the
+    /// bit mask of its alive neighbours (numbered like `MOORE_NEIGHBOURHOOD`)
+    pub fn next_alive(&self, alive: bool, neighbors: u8) -> bool {
+        if alive {
+            self.survival[neighbors as usize]
+        } else {
+            self.birth[neighbors as usize]
+        }
+    }
+}
+
+impl Default for IsotropicRule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl FromStr for IsotropicRule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (birth, survival) = input
+            .trim()
+            .split_once('/')
+            .ok_or(format!("Invalid isotropic rule: {}", input))?;
+        let birth = birth
+            .strip_prefix(['B', 'b'])
+            .ok_or(format!("Missing birth conditions: {}", input))?;
+        let survival = survival
+            .strip_prefix(['S', 's'])
+            .ok_or(format!("Missing survival conditions: {}", input))?;
+
+        Ok(Self {
+            birth: parse_conditions(birth)?,
+            survival: parse_conditions(survival)?,
+        })
+    }
+}
+
+impl fmt::Display for IsotropicRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "B{}/S{}",
+            format_conditions(&self.birth),
+            format_conditions(&self.survival)
+        )
+    }
+}
+
+/// Parse the conditions of a birth or survival, like `2-a3ce`
+fn parse_conditions(input: &str) -> Result<[bool; 256], String> {
+    let classes = classify();
+    let mut conditions = [false; 256];
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let count = match c.to_digit(10) {
+            Some(count) if count < 9 => count as usize,
+            _ => return Err(format!("Invalid neighbour count: {}", c)),
+        };
+
+        let excluded = chars.next_if_eq(&'-').is_some();
+        let mut letters = String::new();
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            if !LETTERS[count].contains(letter) {
+                return Err(format!("Invalid configuration: {}{}", count, letter));
+            }
+            letters.push(letter);
+        }
+
+        classes
+            .iter()
+            .enumerate()
+            .filter(|(_, (class_count, _))| *class_count == count)
+            .filter(|(_, (_, letter))| letters.is_empty() || letters.contains(*letter) != excluded)
+            .for_each(|(mask, _)| conditions[mask] = true);
+    }
+
+    Ok(conditions)
+}
+
+/// Write the conditions of a birth or survival, using
+/// the shortest notation for each count
+fn format_conditions(conditions: &[bool; 256]) -> String {
+    let classes = classify();
+    let mut result = String::new();
+
+    for (count, letters) in LETTERS.iter().enumerate() {
+        let included: String = letters
+            .chars()
+            .filter(|&letter| {
+                classes
+                    .iter()
+                    .zip(conditions)
+                    .any(|(&class, &condition)| condition && class == (count, letter))
+            })
+            .collect();
+
+        let is_included = |mask: usize| conditions[mask];
+        let all = (0..256)
+            .filter(|&mask| classes[mask].0 == count)
+            .all(is_included);
+
+        if all {
+            result.push_str(&count.to_string());
+        } else if !included.is_empty() {
+            let excluded: String = letters.chars().filter(|&l| !included.contains(l)).collect();
+
+            result.push_str(&count.to_string());
+            if excluded.len() < included.len() {
+                result.push('-');
+                result.push_str(&excluded);
+            } else {
+                result.push_str(&included);
+            }
+        }
+    }
+
+    result
+}
+
+/// Universe of an isotropic non-totalistic rule
+#[wasm_bindgen]
+pub struct Isotropic {
+    tick_count: u32,
+    width: u32,
+    height: u32,
+    cells: Vec<Cell>,
+    rule: IsotropicRule,
+}
+
+#[wasm_bindgen]
+impl Isotropic {
+    /// Instantiate a new empty universe following the Conway's game of life
+    pub fn new(width: u32, height: u32) -> Isotropic {
+        // Install panic hook
+        set_panic_hook();
+
+        Isotropic {
+            tick_count: 0,
+            width,
+            height,
+            cells: vec![Cell::Dead; (width * height) as usize],
+            rule: IsotropicRule::default(),
+        }
+    }
+
+    /// Set the rule of the universe, written in the Hensel
+    /// notation (for instance `B2-a/S12`)
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), String> {
+        self.rule = rule.parse()?;
+        Ok(())
+    }
+
+    /// Rule of the universe, in the Hensel notation
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Move the universe to its next state
+    pub fn tick(&mut self) {
+        self.tick_count += 1;
+        self.step();
+    }
+
+    /// Toggle the state of a cell in the universe
+    pub fn toggle_cell(&mut self, row: u32, column: u32) {
+        let idx = self.get_cell_index(row, column);
+        self.cells[idx].toggle();
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn cells(&self) -> *const Cell {
+        self.cells.as_ptr()
+    }
+
+    /// Number of cells behind the `cells` pointer
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Copy of the cells, one byte per cell
+    pub fn cells_array(&self) -> Uint8Array {
+        cell_bytes(&self.cells)
+    }
+
+    /// Number of generations computed since the universe creation
+    pub fn generation(&self) -> u32 {
+        self.tick_count
+    }
+
+    /// Given the position of a cell in the universe, return
+    /// its index in the storage vector
+    fn get_cell_index(&self, row: u32, column: u32) -> usize {
+        ((row * self.width) + column) as usize
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl Isotropic {
+    /// Get the dead and alive values of the entire universe.
+    pub fn get_cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    /// Set cells to be alive in a universe by passing the row and column
+    /// of each cell as an array.
+    pub fn set_cells_alive(&mut self, cells: Vec<(u32, u32)>) {
+        for (row, column) in cells {
+            let cell_index = self.get_cell_index(row, column);
+            self.cells[cell_index] = Cell::Alive;
+        }
+    }
+}
+
+impl Automaton for Isotropic {
+    type State = Cell;
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn grid(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn set_grid(&mut self, grid: Vec<Cell>) {
+        self.cells = grid;
+    }
+
+    fn neighbourhood(&self, _row: u32, _column: u32) -> Vec<(i32, i32)> {
+        MOORE_NEIGHBOURHOOD.to_vec()
+    }
+
+    fn transition(&self, state: Cell, neighbors: &[Cell]) -> Cell {
+        let mask = neighbors
+            .iter()
+            .enumerate()
+            .fold(0, |mask, (index, &cell)| mask | (cell as u8) << index);
+
+        if self.rule.next_alive(state == Cell::Alive, mask) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Universe;
+
+    #[test]
+    fn test_classify() {
+        let classes = classify();
+
+        // Every configuration has a letter matching its neighbour count
+        classes
+            .iter()
+            .enumerate()
+            .for_each(|(mask, &(count, letter))| {
+                assert_eq!(count, (mask as u8).count_ones() as usize);
+                assert!(count == 0 || count == 8 || LETTERS[count].contains(letter));
+            });
+
+        // Each letter is a different configuration
+        (0..9).for_each(|count| {
+            let mut letters: Vec<char> = classes
+                .iter()
+                .filter(|(class_count, _)| *class_count == count)
+                .map(|&(_, letter)| letter)
+                .collect();
+            letters.sort_unstable();
+            letters.dedup();
+            assert_eq!(letters.len(), LETTERS[count].len().max(1));
+        });
+
+        // A single corner and a single edge
+        assert_eq!(classes[0b0000_0010], (1, 'c'));
+        assert_eq!(classes[0b0100_0000], (1, 'e'));
+        // All the neighbours but an edge
+        assert_eq!(classes[0b1111_1011], (7, 'e'));
+    }
+
+    #[test]
+    fn test_parse() {
+        let rule: IsotropicRule = "B2-a/S12".parse().unwrap();
+        // Two adjacent neighbours (north and north-east)
+        assert!(!rule.next_alive(false, 0b0000_0011));
+        // Two opposite neighbours (north and south)
+        assert!(rule.next_alive(false, 0b0001_0001));
+        assert!(rule.next_alive(true, 0b0000_0001));
+        assert!(!rule.next_alive(true, 0b0000_0111));
+
+        assert_eq!(rule.to_string(), "B2-a/S12");
+        assert_eq!(IsotropicRule::conway().to_string(), "B3/S23");
+        assert_eq!(
+            "B3/S2-i34q".parse::<IsotropicRule>().unwrap().to_string(),
+            "B3/S2-i34q"
+        );
+        assert_eq!(
+            "b2ce3/s".parse::<IsotropicRule>().unwrap().to_string(),
+            "B2ce3/S"
+        );
+
+        assert!("B3S23".parse::<IsotropicRule>().is_err());
+        assert!("B3/23".parse::<IsotropicRule>().is_err());
+        assert!("B1a/S23".parse::<IsotropicRule>().is_err());
+        assert!("B9/S23".parse::<IsotropicRule>().is_err());
+    }
+
+    #[test]
+    fn test_totalistic_rule() {
+        // Without letters, the rule is the totalistic Conway's game of life
+        let mut universe = Universe::new(16, 16);
+        universe.randomize(11, 0.4);
+        let mut isotropic = Isotropic::new(16, 16);
+        isotropic.set_grid(universe.get_cells().to_vec());
+
+        assert_eq!(
+            isotropic.next_generation(),
+            Automaton::next_generation(&universe)
+        );
+    }
+
+    #[test]
+    fn test_tick() {
+        // Cells next to a domino have two neighbours in the `2a`
+        // configuration: the domino is stable unless `2a` is allowed
+        let mut universe = Isotropic::new(8, 8);
+        universe.set_rule("B2-a/S12").unwrap();
+        universe.set_cells_alive(vec![(3, 3), (3, 4)]);
+        let domino = universe.get_cells().to_vec();
+
+        universe.tick();
+        assert_eq!(universe.get_cells(), domino.as_slice());
+        assert_eq!(universe.generation(), 1);
+
+        universe.set_rule("B2/S12").unwrap();
+        universe.tick();
+        assert_ne!(universe.get_cells(), domino.as_slice());
+    }
+}
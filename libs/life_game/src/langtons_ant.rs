@@ -0,0 +1,183 @@
+use crate::automaton::{Automaton, VON_NEUMANN_NEIGHBOURHOOD};
+
+/// Heading of an ant, in the order of `VON_NEUMANN_NEIGHBOURHOOD`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Up = 0,
+    Right = 1,
+    Down = 2,
+    Left = 3,
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Turn a quarter clockwise (`quarters` = 1) or anticlockwise (`quarters` = 3)
+    fn turn(self, quarters: usize) -> Direction {
+        Self::ALL[(self as usize + quarters) % 4]
+    }
+}
+
+/// Represents a cell of a Langton's ant universe: its colour
+/// and the ant standing on it, if any
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AntCell {
+    pub black: bool,
+    pub ant: Option<Direction>,
+}
+
+/// Universe of Langton's ant
+///
+/// An ant on a white cell turns right, on a black cell it turns left. It
+/// then flips the colour of its cell and moves forward. As a cellular
+/// automaton, a cell receives the ant of the neighbour heading to it: when
+/// several ants head to the same cell, they merge.
+pub struct LangtonsAnt {
+    tick_count: u32,
+    width: u32,
+    height: u32,
+    cells: Vec<AntCell>,
+}
+
+impl LangtonsAnt {
+    /// Instantiate a new white universe without any ant
+    pub fn new(width: u32, height: u32) -> LangtonsAnt {
+        LangtonsAnt {
+            tick_count: 0,
+            width,
+            height,
+            cells: vec![AntCell::default(); (width * height) as usize],
+        }
+    }
+
+    /// Move the universe to its next state
+    pub fn tick(&mut self) {
+        self.tick_count += 1;
+        self.step();
+    }
+
+    /// Put an ant on a cell
+    pub fn place_ant(&mut self, row: u32, column: u32, direction: Direction) {
+        let idx = self.get_cell_index(row, column);
+        self.cells[idx].ant = Some(direction);
+    }
+
+    /// Position and heading of every ant, as (row, column, direction)
+    pub fn ants(&self) -> Vec<(u32, u32, Direction)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(|(index, cell)| {
+                let index = index as u32;
+                cell.ant
+                    .map(|direction| (index / self.width, index % self.width, direction))
+            })
+            .collect()
+    }
+
+    /// Denote if a cell is black
+    pub fn is_black(&self, row: u32, column: u32) -> bool {
+        self.cells[self.get_cell_index(row, column)].black
+    }
+
+    /// Number of black cells in the universe
+    pub fn black_cells(&self) -> u32 {
+        self.cells.iter().filter(|cell| cell.black).count() as u32
+    }
+
+    /// Number of generations computed since the universe creation
+    pub fn generation(&self) -> u32 {
+        self.tick_count
+    }
+
+    /// Given the position of a cell in the universe, return
+    /// its index in the storage vector
+    fn get_cell_index(&self, row: u32, column: u32) -> usize {
+        ((row * self.width) + column) as usize
+    }
+}
+
+impl Automaton for LangtonsAnt {
+    type State = AntCell;
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn grid(&self) -> &[AntCell] {
+        &self.cells
+    }
+
+    fn set_grid(&mut self, grid: Vec<AntCell>) {
+        self.cells = grid;
+    }
+
+    fn neighbourhood(&self, _row: u32, _column: u32) -> Vec<(i32, i32)> {
+        VON_NEUMANN_NEIGHBOURHOOD.to_vec()
+    }
+
+    fn transition(&self, state: AntCell, neighbors: &[AntCell]) -> AntCell {
+        // The neighbour in a direction comes here if
+        // it heads to the opposite direction
+        let ant = Direction::ALL
+            .iter()
+            .zip(neighbors)
+            .filter_map(|(side, neighbor)| {
+                let heading = neighbor.ant?.turn(if neighbor.black { 3 } else { 1 });
+                (heading == side.turn(2)).then_some(heading)
+            })
+            .next();
+
+        AntCell {
+            black: state.black != state.ant.is_some(),
+            ant,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_first_steps() {
+        let mut universe = LangtonsAnt::new(10, 10);
+        universe.place_ant(5, 5, Direction::Up);
+
+        // On white cells, the ant turns right and draws a square
+        universe.tick();
+        assert_eq!(universe.ants(), vec![(5, 6, Direction::Right)]);
+        universe.tick();
+        assert_eq!(universe.ants(), vec![(6, 6, Direction::Down)]);
+        universe.tick();
+        universe.tick();
+        assert_eq!(universe.ants(), vec![(5, 5, Direction::Up)]);
+        assert_eq!(universe.black_cells(), 4);
+
+        // Back on a black cell, it turns left and clears it
+        universe.tick();
+        assert_eq!(universe.ants(), vec![(5, 4, Direction::Left)]);
+        assert!(!universe.is_black(5, 5));
+        assert_eq!(universe.black_cells(), 3);
+    }
+
+    #[test]
+    fn test_wrapping() {
+        let mut universe = LangtonsAnt::new(4, 4);
+        universe.place_ant(0, 3, Direction::Up);
+
+        universe.tick();
+        assert_eq!(universe.ants(), vec![(0, 0, Direction::Right)]);
+        assert!(universe.is_black(0, 3));
+        assert_eq!(universe.generation(), 1);
+    }
+}
@@ -1,10 +1,46 @@
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
+use maths::{
+    coordinate::{is_upward_triangle, Coordinate},
+    random::Random,
+    summed_area::SummedAreaTable,
+};
 use wasm_bindgen::prelude::*;
 
-use utils::{log, set_panic_hook};
+use js_sys::Uint8Array;
+use utils::{cell_bytes, log, set_panic_hook};
 
+pub use automaton::Automaton;
+use census::Catalogue;
+pub use census::Census;
+pub use elementary::{Boundary, Elementary, ElementaryRule};
+pub use generations::{Generations, GenerationsRule};
+pub use isotropic::{Isotropic, IsotropicRule};
+pub use langtons_ant::{AntCell, Direction, LangtonsAnt};
+use history::{History, DEFAULT_HISTORY_BUDGET};
+use periodicity::{PeriodDetector, Signature, DEFAULT_PERIOD_DETECTION_DEPTH};
+pub use periodicity::{PatternKind, Periodicity};
+pub use rule::{Neighbourhood, Rule};
+pub use statistics::BoundingBox;
+pub use wireworld::{Wireworld, WireworldCell};
+
+pub mod automaton;
+pub mod census;
+pub mod elementary;
+pub mod generations;
+mod history;
+pub mod isotropic;
+pub mod langtons_ant;
+pub mod patterns;
+mod periodicity;
+mod rule;
+mod statistics;
 mod utils;
+pub mod wireworld;
+pub mod worker;
 
 /// Represents a Cell of the game of life universe
 #[wasm_bindgen]
@@ -22,6 +58,22 @@ pub struct Universe {
     width: u32,
     height: u32,
     cells: Vec<Cell>,
+    rule: Rule,
+    period_detector: PeriodDetector,
+    periodicity: Option<Periodicity>,
+    history: History,
+    /// Indices of the cells that changed to reach the current generation
+    /// from the previous one, `None` when they are unknown (after a
+    /// manual update of the universe, or a step back)
+    changed_cells: Option<Vec<u32>>,
+    /// Catalogue of the last census, built once for its separation
+    catalogue: RefCell<Option<Catalogue>>,
+}
+
+impl From<Cell> for u8 {
+    fn from(cell: Cell) -> u8 {
+        cell as u8
+    }
 }
 
 impl Cell {
@@ -46,6 +98,12 @@ impl Universe {
             width,
             height,
             cells: vec![Cell::Dead; (width * height) as usize],
+            rule: Rule::conway(),
+            period_detector: PeriodDetector::new(width, height, DEFAULT_PERIOD_DETECTION_DEPTH),
+            periodicity: None,
+            history: History::new(DEFAULT_HISTORY_BUDGET),
+            changed_cells: None,
+            catalogue: RefCell::new(None),
         }
     }
 
@@ -56,6 +114,46 @@ impl Universe {
             .for_each(|(x, y)| self.toggle_cell(x, y));
     }
 
+    /// Init universe with a random soup
+    ///
+    /// Each cell is alive with the probability `density`.
+    /// The same seed always generates the same universe.
+    pub fn randomize(&mut self, seed: u32, density: f64) {
+        self.randomize_area(seed, density, 0, 0, self.width, self.height);
+    }
+
+    /// Init universe with a random soup restricted to a rectangle
+    ///
+    /// The rectangle starts at (`row`, `column`) and wraps around the
+    /// universe edges. Every cell outside of it is dead.
+    pub fn randomize_area(
+        &mut self,
+        seed: u32,
+        density: f64,
+        row: u32,
+        column: u32,
+        width: u32,
+        height: u32,
+    ) {
+        let mut random = Random::new(seed as u64);
+
+        self.cells.fill(Cell::Dead);
+
+        for delta_row in 0..height.min(self.height) {
+            for delta_col in 0..width.min(self.width) {
+                if random.next_bool(density) {
+                    let cell_index = self.get_cell_index(
+                        (row % self.height + delta_row) % self.height,
+                        (column % self.width + delta_col) % self.width,
+                    );
+                    self.cells[cell_index] = Cell::Alive;
+                }
+            }
+        }
+
+        self.reset_history();
+    }
+
     /// Basic version of rendering where
     /// the universe is rendered as a string
     pub fn render(&self) -> String {
@@ -65,29 +163,286 @@ impl Universe {
     /// Move the universe to its next state by
     /// calculating the next generation of cells
     pub fn tick(&mut self) {
+        // Remember the initial generation, or the one
+        // following a manual update of the universe
+        if self.period_detector.is_empty() {
+            self.period_detector.record(self.signature());
+        }
+
         self.tick_count += 1;
 
-        log!("Current tick: {}", self.tick_count);
+        // Only the cells close to the last changes can change
+        let next_generation = match &self.changed_cells {
+            Some(changed_cells) => self.next_generation_near(changed_cells),
+            None => self.next_generation(),
+        };
 
-        let mut next_universe = self.cells.clone();
+        // Remember the toggled cells to be able to step back
+        let delta: Vec<u32> = self
+            .cells
+            .iter()
+            .zip(next_generation.iter())
+            .enumerate()
+            .filter(|(_, (current, next))| current != next)
+            .map(|(index, _)| index as u32)
+            .collect();
+        self.history.push(delta.clone());
 
-        for row in 0..self.height {
-            for column in 0..self.width {
-                let cell_index = self.get_cell_index(row, column);
-                let current_cell = self.cells[cell_index];
-                let alive_neighbors = self.count_alive_neighbors_of_cell(row, column);
+        self.cells = next_generation;
+        self.changed_cells = Some(delta);
+
+        let periodicity = self.period_detector.record(self.signature());
+        if let (None, Some(periodicity)) = (self.periodicity, periodicity) {
+            log!(
+                "{:?} detected at generation {}",
+                periodicity.kind,
+                self.tick_count
+            );
+        }
+        self.periodicity = periodicity;
+    }
+
+    /// Move the universe back to its previous generation
+    ///
+    /// Return `false` if the previous generation is not in the history
+    /// anymore (or never was, after a manual update of the universe).
+    pub fn step_back(&mut self) -> bool {
+        let Some(delta) = self.history.pop() else {
+            return false;
+        };
+
+        delta
+            .into_iter()
+            .for_each(|index| self.cells[index as usize].toggle());
+        self.tick_count -= 1;
+
+        // Detected periods rely on generations that are now in the future
+        self.reset_period_detection();
+        self.changed_cells = None;
+
+        true
+    }
+
+    /// Move the universe to the specified generation
+    ///
+    /// Past generations are restored from the history, future ones are
+    /// computed. Return `false` if the generation is older than the
+    /// history, the universe is then left at the oldest known generation.
+    pub fn seek(&mut self, generation: u32) -> bool {
+        while self.tick_count < generation {
+            self.tick();
+        }
+
+        while self.tick_count > generation {
+            if !self.step_back() {
+                return false;
+            }
+        }
+
+        true
+    }
 
-                let next_cell = match (current_cell, alive_neighbors) {
-                    (Cell::Alive, x) if !(2..=3).contains(&x) => Cell::Dead,
-                    (Cell::Dead, 3) => Cell::Alive,
-                    (no_change, _) => no_change,
+    /// Set the memory (in bytes) that can be used to remember
+    /// previous generations
+    pub fn set_history_budget(&mut self, budget: usize) {
+        self.history.set_budget(budget);
+    }
+
+    /// Number of generations that can be undone with `step_back`
+    pub fn history_length(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Approximate memory (in bytes) used to remember previous generations
+    pub fn history_size(&self) -> usize {
+        self.history.size()
+    }
+
+    /// Return the number of alive neighbors of every cell, in the
+    /// neighbourhood of the universe rule
+    ///
+    /// Large neighbourhoods are counted with a summed-area table: a Moore
+    /// neighbourhood costs a constant time whatever the range, other shapes
+    /// are summed row by row.
+    fn count_alive_neighbors(&self) -> Vec<u32> {
+        if self.rule.is_moore_range_1() {
+            return (0..self.height)
+                .flat_map(|row| (0..self.width).map(move |column| (row, column)))
+                .map(|(row, column)| self.count_alive_neighbors_of_cell(row, column) as u32)
+                .collect();
+        }
+
+        let table = self.alive_cells_table();
+        (0..self.cells.len())
+            .map(|index| self.count_alive_neighbors_in_table(&table, index))
+            .collect()
+    }
+
+    /// Summed-area table of the alive cells, padded to
+    /// sum the neighbourhood of the universe rule
+    fn alive_cells_table(&self) -> SummedAreaTable {
+        let values: Vec<u32> = self.cells.iter().map(|&cell| cell as u32).collect();
+        // Triangular neighbourhoods are twice wider than high
+        let padding = match self.rule.neighbourhood {
+            Neighbourhood::Triangular => 2 * self.rule.range,
+            _ => self.rule.range,
+        };
+
+        SummedAreaTable::toroidal(
+            &values,
+            self.width as usize,
+            self.height as usize,
+            padding as usize,
+        )
+    }
+
+    /// Return the number of alive neighbors of the cell at `index`,
+    /// summed from the table of `alive_cells_table`
+    fn count_alive_neighbors_in_table(&self, table: &SummedAreaTable, index: usize) -> u32 {
+        let range = self.rule.range as isize;
+        let neighbourhood = self.rule.neighbourhood;
+        let row = (index / self.width as usize) as isize;
+        let column = (index % self.width as usize) as isize;
+
+        let count = match neighbourhood {
+            Neighbourhood::Moore => {
+                table.sum(column - range, row - range, column + range, row + range)
+            }
+            _ => {
+                // Downward triangles have a mirrored neighbourhood
+                let direction = match neighbourhood {
+                    Neighbourhood::Triangular
+                        if !is_upward_triangle(&Coordinate(column as usize, row as usize)) =>
+                    {
+                        -1
+                    }
+                    _ => 1,
                 };
 
-                next_universe[cell_index] = next_cell;
+                (-range..=range)
+                    .map(|delta_row| {
+                        let (min_col, max_col) =
+                            neighbourhood.columns(range as u32, (delta_row * direction) as i32);
+                        table.sum(
+                            column + min_col as isize,
+                            row + delta_row,
+                            column + max_col as isize,
+                            row + delta_row,
+                        )
+                    })
+                    .sum()
+            }
+        };
+
+        // The cell itself is not one of its neighbors
+        count - self.cells[index] as u32
+    }
+
+    /// Calculate the next generation of cells, knowing the cells that
+    /// changed to reach the current generation
+    ///
+    /// A cell whose neighbourhood did not change keeps its state: only the
+    /// cells close to a change are computed. When the changes are too many
+    /// for it to be worth it, every cell is computed.
+    fn next_generation_near(&self, changed_cells: &[u32]) -> Vec<Cell> {
+        let range = self.rule.range as i64;
+        // Triangular neighbourhoods are twice wider than high
+        let half_width = match self.rule.neighbourhood {
+            Neighbourhood::Triangular => 2 * range,
+            _ => range,
+        };
+        let area = ((2 * half_width + 1) * (2 * range + 1)) as usize;
+        if changed_cells.len() * area >= self.cells.len() {
+            return self.next_generation();
+        }
+
+        // Cells having a changed cell in their neighbourhood
+        let (width, height) = (self.width as i64, self.height as i64);
+        let mut active = vec![false; self.cells.len()];
+        changed_cells.iter().for_each(|&index| {
+            let row = index as i64 / width;
+            let column = index as i64 % width;
+            for delta_row in -range..=range {
+                for delta_col in -half_width..=half_width {
+                    let neighbor_row = (row + delta_row).rem_euclid(height);
+                    let neighbor_col = (column + delta_col).rem_euclid(width);
+                    active[(neighbor_row * width + neighbor_col) as usize] = true;
+                }
+            }
+        });
+
+        // Only the neighbors of the active cells are counted
+        let table = if self.rule.is_moore_range_1() {
+            None
+        } else {
+            Some(self.alive_cells_table())
+        };
+
+        let mut next_universe = self.cells.clone();
+        active
+            .iter()
+            .enumerate()
+            .filter(|(_, &active)| active)
+            .for_each(|(index, _)| {
+                let alive_neighbors = match &table {
+                    Some(table) => self.count_alive_neighbors_in_table(table, index),
+                    None => self.count_alive_neighbors_of_cell(
+                        index as u32 / self.width,
+                        index as u32 % self.width,
+                    ) as u32,
+                };
+
+                next_universe[index] = if self
+                    .rule
+                    .next_alive(self.cells[index] == Cell::Alive, alive_neighbors)
+                {
+                    Cell::Alive
+                } else {
+                    Cell::Dead
+                };
+            });
+
+        next_universe
+    }
+
+    /// Compute the translation invariant signature of the current generation
+    ///
+    /// The hash only depends on the alive cells inside the bounding box,
+    /// relatively to its origin.
+    fn signature(&self) -> Signature {
+        let bounding_box = self.bounding_box();
+        let mut hasher = DefaultHasher::new();
+
+        bounding_box.width.hash(&mut hasher);
+        bounding_box.height.hash(&mut hasher);
+
+        for delta_row in 0..bounding_box.height {
+            for delta_col in 0..bounding_box.width {
+                let row = (bounding_box.row + delta_row) % self.height;
+                let column = (bounding_box.column + delta_col) % self.width;
+                (self.cells[self.get_cell_index(row, column)] as u8).hash(&mut hasher);
             }
         }
 
-        self.cells = next_universe;
+        Signature {
+            generation: self.tick_count,
+            hash: hasher.finish(),
+            row: bounding_box.row,
+            column: bounding_box.column,
+        }
+    }
+
+    /// Forget the generation history after a manual update of the universe
+    fn reset_history(&mut self) {
+        self.reset_period_detection();
+        self.changed_cells = None;
+        self.history.clear();
+    }
+
+    /// Forget the generations used to detect periods
+    fn reset_period_detection(&mut self) {
+        self.period_detector.clear();
+        self.periodicity = None;
     }
 
     /// Given the position of a cell in the universe, return
@@ -126,6 +481,14 @@ impl Universe {
     pub fn toggle_cell(&mut self, row: u32, column: u32) {
         let idx = self.get_cell_index(row, column);
         self.cells[idx].toggle();
+
+        // The toggled cell joins the cells changed by the last tick
+        let changed_cells = self.changed_cells.take();
+        self.reset_history();
+        self.changed_cells = changed_cells.map(|mut changed_cells| {
+            changed_cells.push(idx as u32);
+            changed_cells
+        });
     }
 
     pub fn width(&self) -> u32 {
@@ -136,9 +499,126 @@ impl Universe {
         self.height
     }
 
+    /// Pointer to the cells, one byte per cell
+    ///
+    /// A view built on this pointer over the wasm memory is detached when
+    /// the memory grows: it must be rebuilt, with `cells_len`, after
+    /// every call that may allocate (`tick`, `set_rule`...).
     pub fn cells(&self) -> *const Cell {
         self.cells.as_ptr()
     }
+
+    /// Number of cells behind the `cells` pointer
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Copy of the cells, one byte per cell, that stays
+    /// valid when the wasm memory grows
+    pub fn cells_array(&self) -> Uint8Array {
+        cell_bytes(&self.cells)
+    }
+
+    /// Number of generations computed since the universe creation
+    pub fn generation(&self) -> u32 {
+        self.tick_count
+    }
+
+    /// Number of alive cells in the universe
+    pub fn population(&self) -> u32 {
+        self.cells.iter().filter(|&&cell| cell == Cell::Alive).count() as u32
+    }
+
+    /// Periodic behaviour reached by the universe, if any was detected
+    /// in the remembered generations
+    pub fn periodicity(&self) -> Option<Periodicity> {
+        self.periodicity
+    }
+
+    /// Set the rule of the universe, written in the Larger than Life
+    /// notation (for instance `R5,C0,M1,S34..58,B34..45,NM`)
+    pub fn set_rule(&mut self, rule: &str) -> Result<(), String> {
+        self.rule = rule.parse()?;
+        self.reset_period_detection();
+        self.changed_cells = None;
+        Ok(())
+    }
+
+    /// Rule of the universe, in the Larger than Life notation
+    pub fn rule(&self) -> String {
+        self.rule.to_string()
+    }
+
+    /// Set the number of generations remembered to detect periods
+    ///
+    /// Periods longer than this depth can not be detected.
+    pub fn set_period_detection_depth(&mut self, depth: usize) {
+        self.period_detector.set_depth(depth);
+    }
+
+    /// Split the alive cells into objects, and count them by name
+    ///
+    /// Objects are named after the periodic patterns of the pattern library.
+    /// Alive cells at most `separation` cells away belong to the same object.
+    pub fn census(&self, separation: u32) -> Census {
+        let mut catalogue = self.catalogue.borrow_mut();
+        if catalogue.as_ref().map(Catalogue::separation) != Some(separation) {
+            *catalogue = Some(Catalogue::from_library(separation));
+        }
+
+        catalogue.as_ref().unwrap().census(self)
+    }
+
+    /// Smallest (toroidal) rectangle containing all the alive cells
+    pub fn bounding_box(&self) -> BoundingBox {
+        let mut occupied_rows = vec![false; self.height as usize];
+        let mut occupied_columns = vec![false; self.width as usize];
+
+        self.cells
+            .iter()
+            .enumerate()
+            .filter(|(_, &cell)| cell == Cell::Alive)
+            .for_each(|(index, _)| {
+                occupied_rows[index / self.width as usize] = true;
+                occupied_columns[index % self.width as usize] = true;
+            });
+
+        BoundingBox::from_occupancy(&occupied_rows, &occupied_columns)
+    }
+
+    /// Indices of the cells that changed with the last update of the
+    /// universe, so that only they have to be repainted
+    ///
+    /// When the changes are unknown (after the universe initialisation, a
+    /// step back or a rule change), every cell is returned.
+    pub fn changed_cells(&self) -> Vec<u32> {
+        match &self.changed_cells {
+            Some(changed_cells) => changed_cells.clone(),
+            None => (0..self.width * self.height).collect(),
+        }
+    }
+
+    /// Smallest (toroidal) rectangle containing the cells that changed with
+    /// the last update of the universe (see `changed_cells`)
+    pub fn dirty_rectangle(&self) -> BoundingBox {
+        let Some(changed_cells) = &self.changed_cells else {
+            return BoundingBox {
+                row: 0,
+                column: 0,
+                width: self.width,
+                height: self.height,
+            };
+        };
+
+        let mut dirty_rows = vec![false; self.height as usize];
+        let mut dirty_columns = vec![false; self.width as usize];
+        changed_cells.iter().for_each(|&index| {
+            dirty_rows[(index / self.width) as usize] = true;
+            dirty_columns[(index % self.width) as usize] = true;
+        });
+
+        BoundingBox::from_occupancy(&dirty_rows, &dirty_columns)
+    }
 }
 
 /// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
@@ -155,6 +635,86 @@ impl Universe {
             let cell_index = self.get_cell_index(row, column);
             self.cells[cell_index] = Cell::Alive;
         }
+        self.reset_history();
+    }
+}
+
+impl Automaton for Universe {
+    type State = Cell;
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn grid(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn set_grid(&mut self, grid: Vec<Cell>) {
+        self.cells = grid;
+        self.reset_history();
+    }
+
+    /// Neighbourhood of the universe rule, mirrored
+    /// vertically for downward triangles
+    fn neighbourhood(&self, row: u32, column: u32) -> Vec<(i32, i32)> {
+        let offsets = self.rule.offsets();
+
+        if self.rule.neighbourhood == Neighbourhood::Triangular
+            && !is_upward_triangle(&Coordinate(column as usize, row as usize))
+        {
+            offsets
+                .into_iter()
+                .map(|(delta_row, delta_col)| (-delta_row, delta_col))
+                .collect()
+        } else {
+            offsets
+        }
+    }
+
+    fn transition(&self, state: Cell, neighbors: &[Cell]) -> Cell {
+        let alive_neighbors = neighbors.iter().filter(|&&cell| cell == Cell::Alive).count();
+
+        if self.rule.next_alive(state == Cell::Alive, alive_neighbors as u32) {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    /// Calculate the next generation of cells, without
+    /// updating the universe
+    fn next_generation(&self) -> Vec<Cell> {
+        let mut next_universe = self.cells.clone();
+        let alive_neighbors = self.count_alive_neighbors();
+
+        for row in 0..self.height {
+            for column in 0..self.width {
+                let cell_index = self.get_cell_index(row, column);
+                let current_cell = self.cells[cell_index];
+
+                let next_cell = if self
+                    .rule
+                    .next_alive(current_cell == Cell::Alive, alive_neighbors[cell_index])
+                {
+                    Cell::Alive
+                } else {
+                    Cell::Dead
+                };
+
+                next_universe[cell_index] = next_cell;
+            }
+        }
+
+        next_universe
+    }
+
+    fn step(&mut self) {
+        self.tick();
     }
 }
 
@@ -216,4 +776,269 @@ mod test {
         );
         assert_eq!(parse_string_representation("".to_string()), vec![]);
     }
+
+    #[test]
+    fn test_randomize() {
+        let mut universe = Universe::new(64, 64);
+        universe.randomize(42, 0.3);
+
+        let mut same_seed = Universe::new(64, 64);
+        same_seed.randomize(42, 0.3);
+        assert_eq!(universe.get_cells(), same_seed.get_cells());
+
+        let mut other_seed = Universe::new(64, 64);
+        other_seed.randomize(43, 0.3);
+        assert_ne!(universe.get_cells(), other_seed.get_cells());
+
+        // Density is respected
+        let density = universe.population() as f64 / (64.0 * 64.0);
+        assert!((density - 0.3).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_next_generation_near() {
+        let rules = [
+            "R1,C0,M0,S2..3,B3..3,NM",
+            "R2,C0,M1,S5..9,B6..8,NN",
+            "R1,C0,M0,S2..3,B2..3,NH",
+            "R1,C0,M0,S3..5,B4..5,NT",
+        ];
+
+        rules.iter().for_each(|rule| {
+            let mut universe = Universe::new(40, 30);
+            universe.set_rule(rule).unwrap();
+            universe.randomize_area(3, 0.4, 5, 5, 8, 8);
+
+            // Skipping the stable cells gives the same generations
+            for _ in 0..20 {
+                let next_generation = universe.next_generation();
+                let changed_cells: Vec<u32> = universe
+                    .cells
+                    .iter()
+                    .zip(&next_generation)
+                    .enumerate()
+                    .filter(|(_, (current, next))| current != next)
+                    .map(|(index, _)| index as u32)
+                    .collect();
+                universe.cells = next_generation;
+
+                assert_eq!(
+                    universe.next_generation_near(&changed_cells),
+                    universe.next_generation(),
+                    "{}",
+                    rule
+                );
+            }
+        });
+    }
+
+    #[test]
+    fn test_changed_cells() {
+        let mut universe = Universe::new(10, 8);
+        universe.set_cells_alive(vec![(1, 1), (2, 2)]);
+
+        // Unknown changes: everything is dirty
+        assert_eq!(universe.changed_cells().len(), 80);
+        assert_eq!(
+            universe.dirty_rectangle(),
+            BoundingBox {
+                row: 0,
+                column: 0,
+                width: 10,
+                height: 8
+            }
+        );
+
+        // Changes across the universe edges
+        universe.changed_cells = Some(vec![universe.get_cell_index(7, 9) as u32]);
+        universe.toggle_cell(0, 0);
+        assert_eq!(universe.changed_cells(), vec![79, 0]);
+        assert_eq!(
+            universe.dirty_rectangle(),
+            BoundingBox {
+                row: 7,
+                column: 9,
+                width: 2,
+                height: 2
+            }
+        );
+
+        universe.changed_cells = Some(vec![]);
+        assert!(universe.dirty_rectangle().is_empty());
+    }
+
+    #[test]
+    fn test_cells_len() {
+        let mut universe = Universe::new(12, 5);
+        assert_eq!(universe.cells_len(), 60);
+
+        universe.randomize(1, 0.5);
+        universe.set_rule("R1,C0,M0,S2..3,B3..6,NM").unwrap();
+        assert_eq!(universe.cells_len(), universe.get_cells().len());
+    }
+
+    #[test]
+    fn test_randomize_area() {
+        let mut universe = Universe::new(16, 16);
+        universe.randomize_area(1, 1.0, 14, 2, 4, 3);
+
+        assert_eq!(universe.population(), 12);
+        assert_eq!(
+            universe.bounding_box(),
+            BoundingBox {
+                row: 14,
+                column: 2,
+                width: 4,
+                height: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_randomize_area_far_away() {
+        let mut universe = Universe::new(16, 16);
+        universe.randomize_area(1, 1.0, u32::MAX, u32::MAX, 4, 3);
+
+        // u32::MAX is 15 modulo 16, so the area wraps around the corner
+        assert_eq!(universe.population(), 12);
+        let cell = |row, column| universe.get_cells()[universe.get_cell_index(row, column)];
+        assert_eq!(cell(15, 15), Cell::Alive);
+        assert_eq!(cell(1, 2), Cell::Alive);
+        assert_eq!(cell(2, 2), Cell::Dead);
+    }
+
+    /// Count the alive neighbors of a cell by looking at every cell
+    /// of its neighbourhood
+    fn brute_force_count(universe: &Universe, row: u32, column: u32) -> u32 {
+        let range = universe.rule.range as i32;
+        let neighbourhood = universe.rule.neighbourhood;
+        let mut count = 0;
+
+        let upward = is_upward_triangle(&Coordinate(column as usize, row as usize));
+        let direction = if neighbourhood == Neighbourhood::Triangular && !upward {
+            -1
+        } else {
+            1
+        };
+
+        for delta_row in -range..=range {
+            let (min_col, max_col) = neighbourhood.columns(range as u32, delta_row * direction);
+
+            for delta_col in min_col..=max_col {
+                if delta_row == 0 && delta_col == 0 {
+                    continue;
+                }
+
+                let neighbor_row = (row as i32 + delta_row).rem_euclid(universe.height as i32);
+                let neighbor_col = (column as i32 + delta_col).rem_euclid(universe.width as i32);
+                count += universe.cells
+                    [universe.get_cell_index(neighbor_row as u32, neighbor_col as u32)]
+                    as u32;
+            }
+        }
+
+        count
+    }
+
+    #[test]
+    fn test_count_alive_neighbors() {
+        let rules = [
+            "R1,C0,M0,S2..3,B3..3,NM",
+            "R5,C0,M1,S34..58,B34..45,NM",
+            "R3,C0,M0,S5..9,B4..6,NN",
+            "R4,C0,M1,S10..20,B10..15,NC",
+            "R3,C0,M0,S5..9,B4..6,NH",
+            "R2,C0,M0,S5..9,B4..6,NT",
+            // Neighbourhood larger than the universe
+            "R12,C0,M0,S2..3,B3..3,NM",
+        ];
+
+        rules.iter().for_each(|rule| {
+            let mut universe = Universe::new(20, 14);
+            universe.randomize(7, 0.4);
+            universe.set_rule(rule).unwrap();
+
+            let counts = universe.count_alive_neighbors();
+            for row in 0..universe.height {
+                for column in 0..universe.width {
+                    assert_eq!(
+                        counts[universe.get_cell_index(row, column)],
+                        brute_force_count(&universe, row, column),
+                        "{} at ({}, {})",
+                        rule,
+                        row,
+                        column
+                    );
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_lattice_neighbourhoods() {
+        let data = [("R1,C0,M0,S3..4,B2,NH", 6), ("R1,C0,M0,S3..4,B4,NT", 12)];
+
+        data.iter().for_each(|(rule, neighbourhood_size)| {
+            let mut universe = Universe::new(8, 6);
+            universe.set_rule(rule).unwrap();
+            universe.randomize(0, 1.0);
+
+            assert!(universe
+                .count_alive_neighbors()
+                .iter()
+                .all(|count| count == neighbourhood_size));
+        });
+    }
+
+    #[test]
+    fn test_set_rule() {
+        let mut universe = Universe::new(8, 8);
+        assert_eq!(universe.rule(), "R1,C0,M0,S2..3,B3..3,NM");
+
+        assert!(universe.set_rule("R5,C0,M1,S34..58,B34..45,NM").is_ok());
+        assert_eq!(universe.rule(), "R5,C0,M1,S34..58,B34..45,NM");
+
+        assert!(universe.set_rule("invalid").is_err());
+        assert_eq!(universe.rule(), "R5,C0,M1,S34..58,B34..45,NM");
+    }
+
+    #[test]
+    fn test_bosco_next_generation() {
+        // A filled 11x11 square: the center cell counts the 121 cells
+        // of its neighbourhood and dies of overpopulation
+        let mut universe = Universe::new(32, 32);
+        universe.set_rule("R5,C0,M1,S34..58,B34..45,NM").unwrap();
+        universe.set_cells_alive(
+            (10..21)
+                .flat_map(|row| (10..21).map(move |column| (row, column)))
+                .collect(),
+        );
+
+        let next_generation = universe.next_generation();
+        assert_eq!(next_generation[universe.get_cell_index(15, 15)], Cell::Dead);
+        // A corner counts 36 cells and survives
+        assert_eq!(next_generation[universe.get_cell_index(10, 10)], Cell::Alive);
+        // A dead cell next to a corner counts 25 cells and stays dead
+        assert_eq!(next_generation[universe.get_cell_index(9, 9)], Cell::Dead);
+    }
+
+    #[test]
+    fn test_statistics() {
+        let mut universe = Universe::new(8, 8);
+        assert_eq!(universe.population(), 0);
+        assert!(universe.bounding_box().is_empty());
+
+        // Blinker crossing the right edge of the universe
+        universe.set_cells_alive(vec![(3, 7), (3, 0), (3, 1)]);
+        assert_eq!(universe.population(), 3);
+        assert_eq!(
+            universe.bounding_box(),
+            BoundingBox {
+                row: 3,
+                column: 7,
+                width: 3,
+                height: 1
+            }
+        );
+    }
 }
@@ -0,0 +1,260 @@
+//! Library of famous patterns of the game of life
+//!
+//! It mirrors the patterns offered by the pwa, using the plaintext
+//! format understood by [`crate::Universe::init`].
+
+/// A named pattern of the game of life
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub pattern: &'static str,
+}
+
+pub const PATTERNS: &[Pattern] = &[
+    Pattern {
+        name: "Block",
+        description: "Still block",
+        pattern: "!Name: Block
+            !The most common still life.
+            !www.conwaylife.com/wiki/index.php?title=Block
+            OO
+            OO",
+    },
+    Pattern {
+        name: "Bee hive",
+        description: "Bee hive",
+        pattern: "!Name: Beehive
+            !Author: John Conway
+            !The second most common still life.
+            !www.conwaylife.com/wiki/index.php?title=Beehive
+            .OO
+            O..O
+            .OO",
+    },
+    Pattern {
+        name: "Loaf",
+        description: "Loaf",
+        pattern: "!Name: Loaf
+            !The third most common still life.
+            !www.conwaylife.com/wiki/index.php?title=Loaf
+            .OO
+            O..O
+            .O.O
+            ..O",
+    },
+    Pattern {
+        name: "Boat",
+        description: "Boat",
+        pattern: "!Name: Boat
+            !The only 5-cell still life.
+            !www.conwaylife.com/wiki/index.php?title=Boat
+            OO
+            O.O
+            .O",
+    },
+    Pattern {
+        name: "Tub",
+        description: "Tub",
+        pattern: "!Name: Tub
+            !A very common still life.
+            !www.conwaylife.com/wiki/index.php?title=Tub
+            .O
+            O.O
+            .O",
+    },
+    Pattern {
+        name: "Blinker",
+        description: "Period 2",
+        pattern: "!Name: Blinker
+            !Author: John Conway
+            !The smallest and most common oscillator.
+            !www.conwaylife.com/wiki/index.php?title=Blinker
+            OOO",
+    },
+    Pattern {
+        name: "Toad",
+        description: "Period 2",
+        pattern: "!Name: Toad
+            !Author: Simon Norton
+            !The second most common oscillator (after the blinker).
+            !www.conwaylife.com/wiki/index.php?title=Toad
+            .OOO
+            OOO",
+    },
+    Pattern {
+        name: "Beacon",
+        description: "Period 2",
+        pattern: "!Name: Beacon
+            !Author: John Conway
+            !The third most common oscillator (after the blinker and toad).
+            !www.conwaylife.com/wiki/index.php?title=Beacon
+            OO
+            O
+            ...O
+            ..OO",
+    },
+    Pattern {
+        name: "Pulsar",
+        description: "Period 3",
+        pattern: "!Name: Pulsar
+            !Author: John Conway
+            !Despite its size, this is the fourth most common oscillator (and by far the most common of period greater than 2).
+            !www.conwaylife.com/wiki/index.php?title=Pulsar
+            ..OOO...OOO
+            .
+            O....O.O....O
+            O....O.O....O
+            O....O.O....O
+            ..OOO...OOO
+            .
+            ..OOO...OOO
+            O....O.O....O
+            O....O.O....O
+            O....O.O....O
+            .
+            ..OOO...OOO",
+    },
+    Pattern {
+        name: "Penta-decathlon",
+        description: "Period 15",
+        pattern: "!Name: Pentadecathlon
+            !Author: John Conway
+            !10 cells placed in a row evolve into this object, which is the most natural oscillator of period greater than 3. In fact, it is the fifth or sixth most common oscillator overall, being about as frequent as the clock, but much less frequent than the blinker, toad, beacon or pulsar.
+            !www.conwaylife.com/wiki/index.php?title=Pentadecathlon
+            ..O....O
+            OO.OOOO.OO
+            ..O....O",
+    },
+    Pattern {
+        name: "Glide spaceship",
+        description: "",
+        pattern: "!Name: Glider
+            !Author: Richard K. Guy
+            !The smallest, most common, and first discovered spaceship.
+            !www.conwaylife.com/wiki/index.php?title=Glider
+            .O
+            ..O
+            OOO",
+    },
+    Pattern {
+        name: "Light-weight spaceship",
+        description: "",
+        pattern: "!Name: LWSS
+            !Author: John Conway
+            !The smallest known orthogonally moving spaceship, and the second most common spaceship (after the glider).
+            !www.conwaylife.com/wiki/index.php?title=Lightweight_spaceship
+            .O..O
+            O
+            O...O
+            OOOO",
+    },
+    Pattern {
+        name: "Middle-weight spaceship",
+        description: "",
+        pattern: "!Name: MWSS
+            !Author: John Conway
+            !The third most common spaceship (after the glider and lightweight spaceship).
+            !www.conwaylife.com/wiki/index.php?title=Middleweight_spaceship
+            ...O
+            .O...O
+            O
+            O....O
+            OOOOO",
+    },
+    Pattern {
+        name: "Heavy-weight spaceship",
+        description: "",
+        pattern: "!Name: HWSS
+            !Author: John Conway
+            !The fourth most common spaceship (after the glider, lightweight spaceship and middleweight spaceship).
+            !www.conwaylife.com/wiki/index.php?title=Heavyweight_spaceship
+            ...OO
+            .O....O
+            O
+            O.....O
+            OOOOOO",
+    },
+    Pattern {
+        name: "R-pentomino",
+        description: "",
+        pattern: "!Name: R-pentomino
+            !The most active polyomino with less than six cells; all of the others stabilize in at most 10 generations, but the R-pentomino does not do so until generation 1103, by which time it has a population of 116.
+            !www.conwaylife.com/wiki/index.php?title=R-pentomino
+            .OO
+            OO
+            .O",
+    },
+    Pattern {
+        name: "Diehard",
+        description: "",
+        pattern: "!Name: Die hard
+            !A methuselah that vanishes at generation 130, which is conjectured to be maximal for patterns of 7 or fewer cells.
+            !https://www.conwaylife.com/wiki/index.php?title=Die_hard
+            ......O
+            OO
+            .O...OOO",
+    },
+    Pattern {
+        name: "Acorn",
+        description: "",
+        pattern: "!Name: Acorn
+            !Author: Charles Corderman
+            !A methuselah that stabilizes after 5206 generations.
+            !www.conwaylife.com/wiki/index.php?title=Acorn
+            .O
+            ...O
+            OO..OOO",
+    },
+    Pattern {
+        name: "Gosper glider gun",
+        description: "",
+        pattern: "!Name: Gosper glider gun
+            !Author: Bill Gosper
+            !The first known gun and the first known finite pattern with unbounded growth.
+            !www.conwaylife.com/wiki/index.php?title=Gosper_glider_gun
+            ........................O
+            ......................O.O
+            ............OO......OO............OO
+            ...........O...O....OO............OO
+            OO........O.....O...OO
+            OO........O...O.OO....O.O
+            ..........O.....O.......O
+            ...........O...O
+            ............OO",
+    },
+    Pattern {
+        name: "Simkin glider gun",
+        description: "",
+        pattern: "! Simkin glider gun
+            ! Michael Simkin
+            ! A true period 120 glider gun, found on April 28, 2015.
+            ! www.conwaylife.com/wiki/Simkin_glider_gun
+            OO.....OO........................
+            OO.....OO........................
+            .................................
+            ....OO...........................
+            ....OO...........................
+            .................................
+            .................................
+            .................................
+            .................................
+            ......................OO.OO......
+            .....................O.....O.....
+            .....................O......O..OO
+            .....................OOO...O...OO
+            ..........................O......
+            .................................
+            .................................
+            .................................
+            ....................OO...........
+            ....................O............
+            .....................OOO.........
+            .......................O.........",
+    },
+];
+
+/// Find a pattern of the library by name
+pub fn find(name: &str) -> Option<&'static Pattern> {
+    PATTERNS.iter().find(|pattern| pattern.name == name)
+}
@@ -0,0 +1,197 @@
+use std::collections::VecDeque;
+
+use wasm_bindgen::prelude::*;
+
+/// Default number of generations remembered to detect a period
+pub const DEFAULT_PERIOD_DETECTION_DEPTH: usize = 128;
+
+/// Kind of periodic behaviour reached by a universe
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PatternKind {
+    /// The universe does not change anymore
+    StillLife = 0,
+    /// The universe comes back to a previous state, at the same place
+    Oscillator = 1,
+    /// The universe comes back to a previous state, translated
+    Spaceship = 2,
+}
+
+/// Periodic behaviour detected in a universe
+///
+/// `dx` and `dy` are the displacement (in columns and rows) of the
+/// pattern over one `period`.
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Periodicity {
+    pub kind: PatternKind,
+    pub period: u32,
+    pub dx: i32,
+    pub dy: i32,
+}
+
+/// Translation invariant fingerprint of a generation
+///
+/// `hash` only depends on the shape of the alive cells, while `row`
+/// and `column` locate the shape in the universe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub generation: u32,
+    pub hash: u64,
+    pub row: u32,
+    pub column: u32,
+}
+
+/// Detect periods by remembering the signatures of the last generations
+///
+/// Two generations with the same hash are considered identical: the
+/// history only stores hashes to stay lightweight.
+#[derive(Clone, Debug)]
+pub struct PeriodDetector {
+    depth: usize,
+    width: u32,
+    height: u32,
+    history: VecDeque<Signature>,
+}
+
+impl PeriodDetector {
+    /// Instantiate a detector for a universe of the specified size,
+    /// remembering `depth` generations
+    pub fn new(width: u32, height: u32, depth: usize) -> Self {
+        Self {
+            depth,
+            width,
+            height,
+            history: VecDeque::with_capacity(depth),
+        }
+    }
+
+    /// Change the number of remembered generations
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.history.truncate(depth);
+    }
+
+    /// Forget every recorded generation
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.history.is_empty()
+    }
+
+    /// Record the signature of a new generation and return the periodic
+    /// behaviour it reveals, if any
+    ///
+    /// The most recent matching generation is used, so the detected
+    /// period is the smallest one.
+    pub fn record(&mut self, signature: Signature) -> Option<Periodicity> {
+        let periodicity = self
+            .history
+            .iter()
+            .find(|previous| previous.hash == signature.hash)
+            .map(|previous| {
+                let period = signature.generation - previous.generation;
+                let dx = periodic_difference(previous.column, signature.column, self.width);
+                let dy = periodic_difference(previous.row, signature.row, self.height);
+
+                let kind = if dx != 0 || dy != 0 {
+                    PatternKind::Spaceship
+                } else if period == 1 {
+                    PatternKind::StillLife
+                } else {
+                    PatternKind::Oscillator
+                };
+
+                Periodicity {
+                    kind,
+                    period,
+                    dx,
+                    dy,
+                }
+            });
+
+        if self.depth > 0 {
+            if self.history.len() == self.depth {
+                self.history.pop_back();
+            }
+            self.history.push_front(signature);
+        }
+
+        periodicity
+    }
+}
+
+/// Shortest signed difference from `source` to `destination`
+/// on a periodic axis of size `size`
+fn periodic_difference(source: u32, destination: u32, size: u32) -> i32 {
+    let difference = (destination + size - source) % size;
+
+    if difference > size / 2 {
+        difference as i32 - size as i32
+    } else {
+        difference as i32
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn signature(generation: u32, hash: u64, row: u32, column: u32) -> Signature {
+        Signature {
+            generation,
+            hash,
+            row,
+            column,
+        }
+    }
+
+    #[test]
+    fn test_periodic_difference() {
+        assert_eq!(periodic_difference(2, 3, 10), 1);
+        assert_eq!(periodic_difference(3, 2, 10), -1);
+        assert_eq!(periodic_difference(9, 0, 10), 1);
+        assert_eq!(periodic_difference(0, 9, 10), -1);
+    }
+
+    #[test]
+    fn test_record() {
+        let mut detector = PeriodDetector::new(10, 10, 2);
+
+        assert_eq!(detector.record(signature(0, 1, 0, 0)), None);
+        assert_eq!(detector.record(signature(1, 2, 0, 0)), None);
+        assert_eq!(
+            detector.record(signature(2, 1, 9, 1)),
+            Some(Periodicity {
+                kind: PatternKind::Spaceship,
+                period: 2,
+                dx: 1,
+                dy: -1
+            })
+        );
+        assert_eq!(
+            detector.record(signature(3, 2, 0, 0)),
+            Some(Periodicity {
+                kind: PatternKind::Oscillator,
+                period: 2,
+                dx: 0,
+                dy: 0
+            })
+        );
+        assert_eq!(detector.record(signature(4, 3, 0, 0)), None);
+        assert_eq!(
+            detector.record(signature(5, 3, 0, 0)),
+            Some(Periodicity {
+                kind: PatternKind::StillLife,
+                period: 1,
+                dx: 0,
+                dy: 0
+            })
+        );
+        // Generation 3 has been forgotten
+        assert_eq!(detector.record(signature(6, 2, 0, 0)), None);
+    }
+}
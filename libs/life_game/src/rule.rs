@@ -0,0 +1,309 @@
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Shape of the neighbourhood of a cell
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    /// Square of side `2 * range + 1`
+    Moore,
+    /// Diamond: cells at a manhattan distance of at most `range`
+    VonNeumann,
+    /// Disc: cells at an euclidean distance of at most `range`
+    Circular,
+    /// Hexagon: cells at most `range` steps away on a hexagonal grid
+    /// stored in axial coordinates (see `maths::coordinate::HEX_DIRECTIONS`)
+    Hexagonal,
+    /// Triangles sharing a vertex with the cell (12 cells for a range of 1),
+    /// extended ring by ring for larger ranges, on a triangular grid (see
+    /// `maths::coordinate::is_upward_triangle`)
+    Triangular,
+}
+
+impl Neighbourhood {
+    /// Columns of the neighbourhood (relatively to the cell), on the row
+    /// at `delta_row` rows from the cell, bounds included
+    ///
+    /// Triangular neighbourhoods are given for upward triangles, the ones
+    /// of downward triangles are mirrored vertically.
+    pub fn columns(&self, range: u32, delta_row: i32) -> (i32, i32) {
+        let range = range as i32;
+        let half_width = |half_width: i32| (-half_width, half_width);
+
+        match self {
+            Neighbourhood::Moore => half_width(range),
+            Neighbourhood::VonNeumann => half_width(range - delta_row.abs()),
+            Neighbourhood::Circular => {
+                half_width(((range * range - delta_row * delta_row) as f64).sqrt() as i32)
+            }
+            Neighbourhood::Hexagonal => (
+                (delta_row - range).max(-range),
+                (delta_row + range).min(range),
+            ),
+            Neighbourhood::Triangular => half_width(2 * range + delta_row.min(0)),
+        }
+    }
+}
+
+/// Largest range of a rule, the one supported by Golly
+pub const MAX_RANGE: u32 = 500;
+
+/// Larger than Life rule
+///
+/// A cell counts the alive cells in a neighbourhood of radius `range`,
+/// including itself if `include_center` is set. An alive cell survives
+/// if the count is in `survival`, a dead cell is born if it is in `birth`.
+///
+/// Rules are written in the Golly notation, for instance the Conway's game
+/// of life is `R1,C0,M0,S2..3,B3..3,NM`. Triangular neighbourhoods, that
+/// Golly does not support, are written `NT`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub range: u32,
+    pub neighbourhood: Neighbourhood,
+    pub include_center: bool,
+    pub survival: RangeInclusive<u32>,
+    pub birth: RangeInclusive<u32>,
+}
+
+impl Rule {
+    /// Rule of the Conway's game of life
+    pub fn conway() -> Self {
+        Self {
+            range: 1,
+            neighbourhood: Neighbourhood::Moore,
+            include_center: false,
+            survival: 2..=3,
+            birth: 3..=3,
+        }
+    }
+
+    /// Bosco's rule, the most famous Larger than Life rule
+    pub fn bosco() -> Self {
+        "R5,C0,M1,S34..58,B34..45,NM".parse().unwrap()
+    }
+
+    /// Denote if the rule only uses the immediate neighbours of the cells
+    pub fn is_moore_range_1(&self) -> bool {
+        self.range == 1 && self.neighbourhood == Neighbourhood::Moore
+    }
+
+    /// Position of the neighbours of a cell, as (row, column) offsets
+    /// (of an upward cell for triangular neighbourhoods)
+    pub fn offsets(&self) -> Vec<(i32, i32)> {
+        let range = self.range as i32;
+
+        (-range..=range)
+            .flat_map(|delta_row| {
+                let (min_col, max_col) = self.neighbourhood.columns(self.range, delta_row);
+                (min_col..=max_col).map(move |delta_col| (delta_row, delta_col))
+            })
+            .filter(|&offset| offset != (0, 0))
+            .collect()
+    }
+
+    /// Compute the next state of a cell from its current
+    /// state and its number of alive neighbours
+    pub fn next_alive(&self, alive: bool, alive_neighbors: u32) -> bool {
+        let count = alive_neighbors + (alive && self.include_center) as u32;
+
+        if alive {
+            self.survival.contains(&count)
+        } else {
+            self.birth.contains(&count)
+        }
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Self::conway()
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut range = None;
+        let mut include_center = None;
+        let mut survival = None;
+        let mut birth = None;
+        let mut neighbourhood = None;
+
+        for token in input.trim().split(',').map(str::trim) {
+            let (key, value) = token.split_at(token.chars().next().map_or(0, char::len_utf8));
+
+            match key {
+                "R" => range = Some(parse_number(value, token)?),
+                "C" => match parse_number(value, token)? {
+                    0 | 2 => {}
+                    _ => return Err(format!("Multi-state rules are not supported: {}", token)),
+                },
+                "M" => {
+                    include_center = Some(match parse_number(value, token)? {
+                        0 => false,
+                        1 => true,
+                        _ => return Err(format!("Invalid middle cell flag: {}", token)),
+                    })
+                }
+                "S" => survival = Some(parse_interval(value, token)?),
+                "B" => birth = Some(parse_interval(value, token)?),
+                "N" => {
+                    neighbourhood = Some(match value {
+                        "M" => Neighbourhood::Moore,
+                        "N" => Neighbourhood::VonNeumann,
+                        "C" => Neighbourhood::Circular,
+                        "H" => Neighbourhood::Hexagonal,
+                        "T" => Neighbourhood::Triangular,
+                        _ => return Err(format!("Invalid neighbourhood: {}", token)),
+                    })
+                }
+                _ => return Err(format!("Invalid rule component: {}", token)),
+            }
+        }
+
+        let range = range.ok_or("Missing rule range")?;
+        if range == 0 {
+            return Err("Rule range can not be null".to_string());
+        }
+        if range > MAX_RANGE {
+            return Err(format!(
+                "Rule range can not exceed {}: {}",
+                MAX_RANGE, range
+            ));
+        }
+
+        Ok(Self {
+            range,
+            neighbourhood: neighbourhood.unwrap_or(Neighbourhood::Moore),
+            include_center: include_center.unwrap_or(false),
+            survival: survival.ok_or("Missing survival interval")?,
+            birth: birth.ok_or("Missing birth interval")?,
+        })
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let neighbourhood = match self.neighbourhood {
+            Neighbourhood::Moore => 'M',
+            Neighbourhood::VonNeumann => 'N',
+            Neighbourhood::Circular => 'C',
+            Neighbourhood::Hexagonal => 'H',
+            Neighbourhood::Triangular => 'T',
+        };
+
+        write!(
+            f,
+            "R{},C0,M{},S{}..{},B{}..{},N{}",
+            self.range,
+            self.include_center as u8,
+            self.survival.start(),
+            self.survival.end(),
+            self.birth.start(),
+            self.birth.end(),
+            neighbourhood
+        )
+    }
+}
+
+fn parse_number(value: &str, token: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid number in rule component: {}", token))
+}
+
+/// Parse an interval written `min..max`, or a single value
+fn parse_interval(value: &str, token: &str) -> Result<RangeInclusive<u32>, String> {
+    match value.split_once("..") {
+        Some((min, max)) => {
+            let (min, max) = (parse_number(min, token)?, parse_number(max, token)?);
+            if min > max {
+                return Err(format!("Reversed interval: {}", token));
+            }
+
+            Ok(min..=max)
+        }
+        None => {
+            let value = parse_number(value, token)?;
+            Ok(value..=value)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "R5,C0,M1,S34..58,B34..45,NM".parse::<Rule>(),
+            Ok(Rule {
+                range: 5,
+                neighbourhood: Neighbourhood::Moore,
+                include_center: true,
+                survival: 34..=58,
+                birth: 34..=45,
+            })
+        );
+        assert_eq!("R1,C0,M0,S2..3,B3,NM".parse::<Rule>(), Ok(Rule::conway()));
+        assert_eq!(
+            "R2,M0,S3..4,B3..3,NN"
+                .parse::<Rule>()
+                .unwrap()
+                .neighbourhood,
+            Neighbourhood::VonNeumann
+        );
+        assert_eq!(
+            "R1,M0,S3..4,B2,NH".parse::<Rule>().unwrap().to_string(),
+            "R1,C0,M0,S3..4,B2..2,NH"
+        );
+
+        assert!("R1,C3,M0,S2..3,B3..3,NM".parse::<Rule>().is_err());
+        assert!("R1,M0,S2..3,NM".parse::<Rule>().is_err());
+        assert!("R0,M0,S2..3,B3,NM".parse::<Rule>().is_err());
+        assert!("R1,M0,S2..x,B3,NM".parse::<Rule>().is_err());
+        assert!("R1,M0,S2..3,B3,NX".parse::<Rule>().is_err());
+        assert!("".parse::<Rule>().is_err());
+        assert!("R1,M7,S2..3,B3,NM".parse::<Rule>().is_err());
+        assert!("R5,M1,S58..34,B34..45,NM".parse::<Rule>().is_err());
+        assert!("R5,M1,S34..58,B45..34,NM".parse::<Rule>().is_err());
+        assert!("R100000,M0,S2..3,B3,NM".parse::<Rule>().is_err());
+        assert!(format!("R{},M0,S2..3,B3,NM", MAX_RANGE)
+            .parse::<Rule>()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Rule::bosco().to_string(), "R5,C0,M1,S34..58,B34..45,NM");
+        assert_eq!(
+            Rule::conway().to_string().parse::<Rule>(),
+            Ok(Rule::conway())
+        );
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(Neighbourhood::Moore.columns(3, -2), (-3, 3));
+        assert_eq!(Neighbourhood::VonNeumann.columns(3, -2), (-1, 1));
+        assert_eq!(Neighbourhood::Circular.columns(5, 3), (-4, 4));
+        assert_eq!(Neighbourhood::Circular.columns(5, 5), (0, 0));
+        assert_eq!(Neighbourhood::Hexagonal.columns(2, -1), (-2, 1));
+        assert_eq!(Neighbourhood::Hexagonal.columns(2, 2), (0, 2));
+        assert_eq!(Neighbourhood::Triangular.columns(1, -1), (-1, 1));
+        assert_eq!(Neighbourhood::Triangular.columns(1, 1), (-2, 2));
+    }
+
+    #[test]
+    fn test_offsets() {
+        assert_eq!(Rule::conway().offsets().len(), 8);
+        assert_eq!(Rule::bosco().offsets().len(), 120);
+        assert_eq!(
+            "R1,M0,S2,B2,NN".parse::<Rule>().unwrap().offsets(),
+            vec![(-1, 0), (0, -1), (0, 1), (1, 0)]
+        );
+    }
+}
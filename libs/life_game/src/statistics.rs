@@ -0,0 +1,108 @@
+use wasm_bindgen::prelude::*;
+
+/// Smallest rectangle containing every alive cell of a universe
+///
+/// The universe is toroidal, so the rectangle can cross its edges: it then
+/// starts at (`row`, `column`) and wraps around. An empty universe has an
+/// empty bounding box.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub row: u32,
+    pub column: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[wasm_bindgen]
+impl BoundingBox {
+    /// Denote if the bounding box contains no cell
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+}
+
+impl BoundingBox {
+    /// Compute the bounding box from the occupied rows and columns
+    /// of a universe
+    pub fn from_occupancy(occupied_rows: &[bool], occupied_columns: &[bool]) -> Self {
+        match (
+            periodic_span(occupied_rows),
+            periodic_span(occupied_columns),
+        ) {
+            (Some((row, height)), Some((column, width))) => Self {
+                row,
+                column,
+                width,
+                height,
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Find the shortest circular interval containing all the occupied positions
+///
+/// The interval is the complement of the longest circular run of free
+/// positions. Return its start and length, or `None` if nothing is occupied.
+fn periodic_span(occupied: &[bool]) -> Option<(u32, u32)> {
+    let size = occupied.len();
+    let first_occupied = occupied.iter().position(|&o| o)?;
+
+    // Walk the positions starting from an occupied one, so that
+    // free runs never need to wrap around
+    let mut longest_gap = (0, first_occupied);
+    let mut gap_length = 0;
+
+    for offset in 1..=size {
+        let position = (first_occupied + offset) % size;
+
+        if occupied[position] {
+            if gap_length > longest_gap.0 {
+                longest_gap = (gap_length, position);
+            }
+            gap_length = 0;
+        } else {
+            gap_length += 1;
+        }
+    }
+
+    let (gap_length, start) = longest_gap;
+
+    Some((start as u32, (size - gap_length) as u32))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_periodic_span() {
+        assert_eq!(periodic_span(&[false, false, false]), None);
+        assert_eq!(periodic_span(&[true, true, true]), Some((0, 3)));
+        assert_eq!(periodic_span(&[false, true, false, false]), Some((1, 1)));
+        assert_eq!(
+            periodic_span(&[false, true, true, false, true, false, false]),
+            Some((1, 4))
+        );
+        // Span crossing the edges
+        assert_eq!(
+            periodic_span(&[true, false, false, false, true, true]),
+            Some((4, 3))
+        );
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        assert!(BoundingBox::from_occupancy(&[false; 4], &[false; 4]).is_empty());
+        assert_eq!(
+            BoundingBox::from_occupancy(&[false, true, true, false], &[true, false, false, true]),
+            BoundingBox {
+                row: 1,
+                column: 3,
+                width: 2,
+                height: 2
+            }
+        );
+    }
+}
@@ -1,4 +1,4 @@
-extern crate web_sys;
+use js_sys::Uint8Array;
 
 pub fn set_panic_hook() {
     // When the `console_error_panic_hook` feature is enabled, we can call the
@@ -11,10 +11,36 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
-// A macro to provide `println!(..)`-style syntax for `console.log` logging.
+/// Copy cells stored as one byte enums into a typed array
+///
+/// The copy lives in the JS heap: unlike a view over the wasm
+/// memory, it stays valid when the memory grows.
+pub fn cell_bytes<T: Copy + Into<u8>>(cells: &[T]) -> Uint8Array {
+    let bytes: Vec<u8> = cells.iter().map(|&cell| cell.into()).collect();
+    Uint8Array::from(bytes.as_slice())
+}
+
+/// Write a log message to the backend selected by cargo feature:
+/// the browser console with `console_log`, the standard error with
+/// `native_log`, and nowhere otherwise (messages are then not even formatted)
+#[cfg(feature = "console_log")]
+pub fn log_message(message: std::fmt::Arguments) {
+    web_sys::console::log_1(&message.to_string().into());
+}
+
+#[cfg(all(feature = "native_log", not(feature = "console_log")))]
+pub fn log_message(message: std::fmt::Arguments) {
+    eprintln!("{}", message);
+}
+
+#[cfg(not(any(feature = "console_log", feature = "native_log")))]
+#[inline(always)]
+pub fn log_message(_message: std::fmt::Arguments) {}
+
+// A macro to provide `println!(..)`-style syntax for logging.
 macro_rules! log {
     ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
+        crate::utils::log_message(format_args!( $( $t )* ))
     }
 }
 
@@ -0,0 +1,195 @@
+use js_sys::Uint8Array;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    automaton::{Automaton, MOORE_NEIGHBOURHOOD},
+    utils::{cell_bytes, set_panic_hook},
+};
+
+/// Represents a cell of a Wireworld universe
+#[wasm_bindgen]
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WireworldCell {
+    Empty = 0,
+    /// Head of an electron
+    Head = 1,
+    /// Tail of an electron
+    Tail = 2,
+    Conductor = 3,
+}
+
+impl From<WireworldCell> for u8 {
+    fn from(cell: WireworldCell) -> u8 {
+        cell as u8
+    }
+}
+
+/// Universe of Wireworld, an automaton simulating electrons
+/// moving along wires
+///
+/// Electron heads become tails, tails become conductors, and conductors
+/// become heads when one or two of their neighbours are heads.
+#[wasm_bindgen]
+pub struct Wireworld {
+    tick_count: u32,
+    width: u32,
+    height: u32,
+    cells: Vec<WireworldCell>,
+}
+
+#[wasm_bindgen]
+impl Wireworld {
+    /// Instantiate a new empty universe
+    pub fn new(width: u32, height: u32) -> Wireworld {
+        // Install panic hook
+        set_panic_hook();
+
+        Wireworld {
+            tick_count: 0,
+            width,
+            height,
+            cells: vec![WireworldCell::Empty; (width * height) as usize],
+        }
+    }
+
+    /// Move the universe to its next state
+    pub fn tick(&mut self) {
+        self.tick_count += 1;
+        self.step();
+    }
+
+    /// Set the state of a cell of the universe
+    pub fn set_cell(&mut self, row: u32, column: u32, cell: WireworldCell) {
+        let idx = self.get_cell_index(row, column);
+        self.cells[idx] = cell;
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn cells(&self) -> *const WireworldCell {
+        self.cells.as_ptr()
+    }
+
+    /// Number of cells behind the `cells` pointer
+    pub fn cells_len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Copy of the cells, one byte per cell
+    pub fn cells_array(&self) -> Uint8Array {
+        cell_bytes(&self.cells)
+    }
+
+    /// Number of generations computed since the universe creation
+    pub fn generation(&self) -> u32 {
+        self.tick_count
+    }
+
+    /// Given the position of a cell in the universe, return
+    /// its index in the storage vector
+    fn get_cell_index(&self, row: u32, column: u32) -> usize {
+        ((row * self.width) + column) as usize
+    }
+}
+
+/// Methods needed for test (that should not be ported to js/ts by wasm-bindgen)
+impl Wireworld {
+    /// Get the states of the entire universe
+    pub fn get_cells(&self) -> &[WireworldCell] {
+        &self.cells
+    }
+}
+
+impl Automaton for Wireworld {
+    type State = WireworldCell;
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn grid(&self) -> &[WireworldCell] {
+        &self.cells
+    }
+
+    fn set_grid(&mut self, grid: Vec<WireworldCell>) {
+        self.cells = grid;
+    }
+
+    fn neighbourhood(&self, _row: u32, _column: u32) -> Vec<(i32, i32)> {
+        MOORE_NEIGHBOURHOOD.to_vec()
+    }
+
+    fn transition(&self, state: WireworldCell, neighbors: &[WireworldCell]) -> WireworldCell {
+        match state {
+            WireworldCell::Empty => WireworldCell::Empty,
+            WireworldCell::Head => WireworldCell::Tail,
+            WireworldCell::Tail => WireworldCell::Conductor,
+            WireworldCell::Conductor => {
+                let heads = neighbors
+                    .iter()
+                    .filter(|&&cell| cell == WireworldCell::Head)
+                    .count();
+
+                if heads == 1 || heads == 2 {
+                    WireworldCell::Head
+                } else {
+                    WireworldCell::Conductor
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_electron() {
+        // An electron moving to the right along a wire
+        let mut universe = Wireworld::new(8, 3);
+        (0..8).for_each(|column| universe.set_cell(1, column, WireworldCell::Conductor));
+        universe.set_cell(1, 1, WireworldCell::Tail);
+        universe.set_cell(1, 2, WireworldCell::Head);
+
+        universe.tick();
+        universe.tick();
+
+        assert_eq!(universe.get_cells()[8 + 3], WireworldCell::Tail);
+        assert_eq!(universe.get_cells()[8 + 4], WireworldCell::Head);
+        assert_eq!(universe.get_cells()[8 + 2], WireworldCell::Conductor);
+        assert_eq!(universe.get_cells()[8 + 1], WireworldCell::Conductor);
+        // Empty cells never change
+        assert!(universe.get_cells()[..8]
+            .iter()
+            .all(|&cell| cell == WireworldCell::Empty));
+    }
+
+    #[test]
+    fn test_crowded_conductor() {
+        // A conductor next to three heads stays a conductor
+        let universe = Wireworld::new(3, 3);
+        let heads = [WireworldCell::Head; 3];
+        let neighbors = [&heads[..], &[WireworldCell::Empty; 5]].concat();
+
+        assert_eq!(
+            universe.transition(WireworldCell::Conductor, &neighbors),
+            WireworldCell::Conductor
+        );
+        assert_eq!(
+            universe.transition(WireworldCell::Conductor, &neighbors[1..]),
+            WireworldCell::Head
+        );
+    }
+}
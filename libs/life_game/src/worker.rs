@@ -0,0 +1,275 @@
+use js_sys::SharedArrayBuffer;
+use wasm_bindgen::prelude::*;
+use worker::{
+    buffer::{DoubleBuffer, SharedMemory},
+    codec::{Decoder, Encoder},
+    in_process::Handler,
+    protocol::{Client, Simulation, Worker},
+    shared_array::SharedArrayMemory,
+};
+
+use crate::{Cell, Universe};
+
+/// Commands sent by the main thread to a universe running in a worker
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Tick { generations: u32 },
+    StepBack,
+    ToggleCell { row: u32, column: u32 },
+    Randomize { seed: u32, density: f64 },
+    SetRule(String),
+    Snapshot,
+}
+
+impl Command {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Command::Tick { generations } => Encoder::new().u8(0).u32(*generations),
+            Command::StepBack => Encoder::new().u8(1),
+            Command::ToggleCell { row, column } => Encoder::new().u8(2).u32(*row).u32(*column),
+            Command::Randomize { seed, density } => Encoder::new().u8(3).u32(*seed).f64(*density),
+            Command::SetRule(rule) => Encoder::new().u8(4).string(rule),
+            Command::Snapshot => Encoder::new().u8(5),
+        }
+        .finish()
+    }
+
+    pub fn decode(message: &[u8]) -> Result<Self, String> {
+        let mut decoder = Decoder::new(message);
+        let command = match decoder.u8()? {
+            0 => Command::Tick {
+                generations: decoder.u32()?,
+            },
+            1 => Command::StepBack,
+            2 => Command::ToggleCell {
+                row: decoder.u32()?,
+                column: decoder.u32()?,
+            },
+            3 => Command::Randomize {
+                seed: decoder.u32()?,
+                density: decoder.f64()?,
+            },
+            4 => Command::SetRule(decoder.string()?),
+            5 => Command::Snapshot,
+            tag => return Err(format!("Unknown command: {}", tag)),
+        };
+        decoder.finish()?;
+
+        Ok(command)
+    }
+}
+
+impl Simulation for Universe {
+    fn apply(&mut self, message: &[u8]) -> Result<(), String> {
+        match Command::decode(message)? {
+            Command::Tick { generations } => (0..generations).for_each(|_| self.tick()),
+            Command::StepBack => {
+                self.step_back();
+            }
+            Command::ToggleCell { row, column } => {
+                if row >= self.height || column >= self.width {
+                    return Err(format!("Cell ({}, {}) is out of the universe", row, column));
+                }
+                self.toggle_cell(row, column)
+            }
+            Command::Randomize { seed, density } => self.randomize(seed, density),
+            Command::SetRule(rule) => self.set_rule(&rule)?,
+            Command::Snapshot => (),
+        }
+
+        Ok(())
+    }
+
+    fn generation(&self) -> u32 {
+        Universe::generation(self)
+    }
+
+    /// The cells, one byte per cell
+    fn frame(&self) -> Vec<u8> {
+        self.cells.iter().map(|&cell| cell.into()).collect()
+    }
+}
+
+/// Universe running in a Web Worker, driven by encoded commands
+///
+/// The state is sent back in each response, or published in a shared
+/// buffer when one is attached, to avoid copying it through messages.
+#[wasm_bindgen]
+pub struct UniverseWorker {
+    worker: Worker<Universe>,
+}
+
+#[wasm_bindgen]
+impl UniverseWorker {
+    pub fn new(width: u32, height: u32) -> UniverseWorker {
+        UniverseWorker {
+            worker: Worker::new(Universe::new(width, height)),
+        }
+    }
+
+    /// Publish the next states in a `SharedArrayBuffer` of at least
+    /// `shared_buffer_bytes` bytes
+    pub fn attach_shared_buffer(&mut self, buffer: &SharedArrayBuffer) -> Result<(), String> {
+        self.attach_memory(Box::new(SharedArrayMemory::new(buffer)))
+    }
+
+    /// Apply an encoded command, and return the encoded response
+    pub fn handle(&mut self, message: &[u8]) -> Vec<u8> {
+        self.worker.handle(message)
+    }
+}
+
+impl UniverseWorker {
+    /// Publish the next states in a shared memory
+    pub fn attach_memory(&mut self, memory: Box<dyn SharedMemory>) -> Result<(), String> {
+        self.worker.attach_memory(memory)
+    }
+}
+
+impl Handler for UniverseWorker {
+    fn handle(&mut self, message: &[u8]) -> Vec<u8> {
+        UniverseWorker::handle(self, message)
+    }
+}
+
+/// Main thread side of a `UniverseWorker`: encodes the commands, and
+/// reads the states from the responses or from the shared buffer
+#[wasm_bindgen]
+pub struct UniverseClient {
+    client: Client,
+}
+
+#[wasm_bindgen]
+impl UniverseClient {
+    pub fn new(width: u32, height: u32) -> UniverseClient {
+        UniverseClient {
+            client: Client::new(vec![Cell::Dead.into(); (width * height) as usize]),
+        }
+    }
+
+    /// Size (in bytes) of the `SharedArrayBuffer` shared with the worker
+    pub fn shared_buffer_bytes(width: u32, height: u32) -> usize {
+        DoubleBuffer::required_bytes((width * height) as usize)
+    }
+
+    pub fn attach_shared_buffer(&mut self, buffer: &SharedArrayBuffer) -> Result<(), String> {
+        self.attach_memory(Box::new(SharedArrayMemory::new(buffer)))
+    }
+
+    pub fn tick(generations: u32) -> Vec<u8> {
+        Command::Tick { generations }.encode()
+    }
+
+    pub fn step_back() -> Vec<u8> {
+        Command::StepBack.encode()
+    }
+
+    pub fn toggle_cell(row: u32, column: u32) -> Vec<u8> {
+        Command::ToggleCell { row, column }.encode()
+    }
+
+    pub fn randomize(seed: u32, density: f64) -> Vec<u8> {
+        Command::Randomize { seed, density }.encode()
+    }
+
+    pub fn set_rule(rule: &str) -> Vec<u8> {
+        Command::SetRule(rule.to_string()).encode()
+    }
+
+    pub fn snapshot() -> Vec<u8> {
+        Command::Snapshot.encode()
+    }
+
+    /// Read a response of the worker, updating the generation and the cells
+    pub fn receive(&mut self, message: &[u8]) -> Result<(), String> {
+        self.client.receive(message)
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.client.generation()
+    }
+
+    /// Copy of the last state received, one byte per cell
+    pub fn cells(&self) -> Vec<u8> {
+        self.client.frame().to_vec()
+    }
+}
+
+impl UniverseClient {
+    /// Read the states published by the worker in a shared memory
+    pub fn attach_memory(&mut self, memory: Box<dyn SharedMemory>) -> Result<(), String> {
+        self.client.attach_memory(memory)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use worker::{buffer::InProcessMemory, in_process::InProcessWorker, protocol::Response};
+
+    #[test]
+    fn test_commands() {
+        [
+            Command::Tick { generations: 3 },
+            Command::StepBack,
+            Command::ToggleCell { row: 1, column: 2 },
+            Command::Randomize {
+                seed: 4,
+                density: 0.5,
+            },
+            Command::SetRule("R1,C0,M0,S2..3,B3,NM".to_string()),
+            Command::Snapshot,
+        ]
+        .into_iter()
+        .for_each(|command| assert_eq!(Command::decode(&command.encode()), Ok(command)));
+
+        assert!(Command::decode(&[9]).is_err());
+        assert!(Command::decode(&[1, 0]).is_err());
+    }
+
+    #[test]
+    fn test_messages() {
+        let worker = InProcessWorker::spawn(|| UniverseWorker::new(4, 3));
+        let mut client = UniverseClient::new(4, 3);
+
+        client
+            .receive(&worker.request(UniverseClient::toggle_cell(1, 2)))
+            .unwrap();
+        assert_eq!(client.generation(), 0);
+        assert_eq!(client.cells()[4 + 2], u8::from(Cell::Alive));
+        assert_eq!(client.cells().iter().filter(|&&cell| cell == 1).count(), 1);
+
+        // Errors of the worker are reported to the client
+        assert!(client
+            .receive(&worker.request(UniverseClient::toggle_cell(3, 0)))
+            .is_err());
+        assert!(client
+            .receive(&worker.request(UniverseClient::set_rule("B3")))
+            .is_err());
+    }
+
+    #[test]
+    fn test_shared_buffer() {
+        let memory = InProcessMemory::new(UniverseClient::shared_buffer_bytes(8, 8));
+        let worker_memory = memory.clone();
+        let worker = InProcessWorker::spawn(move || {
+            let mut worker = UniverseWorker::new(8, 8);
+            worker.attach_memory(Box::new(worker_memory)).unwrap();
+            worker
+        });
+        let mut client = UniverseClient::new(8, 8);
+        client.attach_memory(Box::new(memory)).unwrap();
+
+        let response = worker.request(UniverseClient::randomize(7, 0.5));
+        assert_eq!(
+            Response::decode(&response),
+            Ok(Response::Published { generation: 0 })
+        );
+        client.receive(&response).unwrap();
+
+        let mut universe = Universe::new(8, 8);
+        universe.randomize(7, 0.5);
+        let expected: Vec<u8> = universe.cells.iter().map(|&cell| cell.into()).collect();
+        assert_eq!(client.cells(), expected);
+    }
+}
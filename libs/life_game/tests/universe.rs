@@ -1,20 +1,14 @@
-//! Test suite for the Web and headless browsers.
-#![cfg(target_arch = "wasm32")]
+//! Test suite of the universe, run natively.
 
 extern crate life_game;
-extern crate wasm_bindgen_test;
 
-use life_game::Universe;
-use wasm_bindgen_test::*;
+use life_game::{
+    patterns,
+    worker::{UniverseClient, UniverseWorker},
+    BoundingBox, Cell, PatternKind, Periodicity, Universe,
+};
 
-wasm_bindgen_test_configure!(run_in_browser);
-
-#[wasm_bindgen_test]
-fn pass() {
-    assert_eq!(1 + 1, 2);
-}
-
-#[wasm_bindgen_test]
+#[test]
 pub fn test_tick() {
     // Let's create a smaller Universe with a small spaceship to test!
     let mut input_universe = Universe::new(6, 6);
@@ -30,3 +24,292 @@ pub fn test_tick() {
 
     assert_eq!(&input_universe.get_cells(), &expected_universe.get_cells());
 }
+
+fn periodicity_of(pattern_name: &str, max_generations: u32) -> Option<Periodicity> {
+    let mut universe = Universe::new(32, 32);
+    universe.init(patterns::find(pattern_name).unwrap().pattern.to_string());
+
+    (0..max_generations).find_map(|_| {
+        universe.tick();
+        universe.periodicity()
+    })
+}
+
+#[test]
+pub fn test_periodicity() {
+    let oscillators = [
+        ("Block", PatternKind::StillLife, 1),
+        ("Blinker", PatternKind::Oscillator, 2),
+        ("Pulsar", PatternKind::Oscillator, 3),
+        ("Penta-decathlon", PatternKind::Oscillator, 15),
+    ];
+
+    oscillators.iter().for_each(|(name, kind, period)| {
+        assert_eq!(
+            periodicity_of(name, 50),
+            Some(Periodicity {
+                kind: *kind,
+                period: *period,
+                dx: 0,
+                dy: 0
+            }),
+            "{}",
+            name
+        )
+    });
+
+    let glider = periodicity_of("Glide spaceship", 50).unwrap();
+    assert_eq!(glider.kind, PatternKind::Spaceship);
+    assert_eq!(glider.period, 4);
+    assert_eq!((glider.dx.abs(), glider.dy.abs()), (1, 1));
+}
+
+#[test]
+pub fn test_periodicity_reset() {
+    let mut universe = Universe::new(8, 8);
+    universe.set_cells_alive(vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+    universe.tick();
+    assert!(universe.periodicity().is_some());
+
+    universe.toggle_cell(5, 5);
+    assert_eq!(universe.periodicity(), None);
+}
+
+#[test]
+pub fn test_step_back() {
+    let mut universe = Universe::new(16, 16);
+    universe.init(patterns::find("Glide spaceship").unwrap().pattern.to_string());
+    let initial_cells = universe.get_cells().to_vec();
+
+    universe.tick();
+    let first_generation = universe.get_cells().to_vec();
+    universe.tick();
+    assert_eq!(universe.history_length(), 2);
+
+    assert!(universe.step_back());
+    assert_eq!(universe.generation(), 1);
+    assert_eq!(universe.get_cells(), first_generation.as_slice());
+
+    assert!(universe.step_back());
+    assert_eq!(universe.generation(), 0);
+    assert_eq!(universe.get_cells(), initial_cells.as_slice());
+
+    // Nothing left to undo
+    assert!(!universe.step_back());
+}
+
+#[test]
+pub fn test_seek() {
+    let mut universe = Universe::new(16, 16);
+    universe.init(patterns::find("Glide spaceship").unwrap().pattern.to_string());
+
+    assert!(universe.seek(8));
+    assert_eq!(universe.generation(), 8);
+    let eighth_generation = universe.get_cells().to_vec();
+
+    assert!(universe.seek(2));
+    assert!(universe.seek(8));
+    assert_eq!(universe.get_cells(), eighth_generation.as_slice());
+
+    // Keep only the last two generations
+    universe.set_history_budget(64);
+    let history_length = universe.history_length();
+    assert!(history_length <= 2);
+    assert!(!universe.seek(0));
+    assert_eq!(universe.generation() as usize, 8 - history_length);
+    assert_eq!(universe.history_length(), 0);
+}
+
+#[test]
+pub fn test_changed_cells() {
+    let mut universe = Universe::new(8, 8);
+    universe.set_cells_alive(vec![(3, 2), (3, 3), (3, 4)]);
+    universe.tick();
+
+    // The blinker turns vertical: the ends flip
+    let mut changed_cells = universe.changed_cells();
+    changed_cells.sort_unstable();
+    assert_eq!(
+        changed_cells,
+        vec![2 * 8 + 3, 3 * 8 + 2, 3 * 8 + 4, 4 * 8 + 3]
+    );
+
+    let dirty_rectangle = universe.dirty_rectangle();
+    assert_eq!(
+        (
+            dirty_rectangle.row,
+            dirty_rectangle.column,
+            dirty_rectangle.width,
+            dirty_rectangle.height
+        ),
+        (2, 2, 3, 3)
+    );
+
+    // Stable regions are skipped, the oscillation goes on
+    universe.tick();
+    universe.tick();
+    assert_eq!(universe.population(), 3);
+    assert_eq!(universe.changed_cells().len(), 4);
+}
+
+#[test]
+pub fn test_worker_tick() {
+    let mut worker = UniverseWorker::new(8, 8);
+    let mut client = UniverseClient::new(8, 8);
+    [(3, 2), (3, 3), (3, 4)].iter().for_each(|&(row, column)| {
+        client
+            .receive(&worker.handle(&UniverseClient::toggle_cell(row, column)))
+            .unwrap()
+    });
+
+    client
+        .receive(&worker.handle(&UniverseClient::tick(3)))
+        .unwrap();
+
+    // After an odd number of generations, the blinker is vertical
+    let mut alive_cells: Vec<usize> = client
+        .cells()
+        .iter()
+        .enumerate()
+        .filter(|(_, &cell)| cell == 1)
+        .map(|(idx, _)| idx)
+        .collect();
+    alive_cells.sort_unstable();
+    assert_eq!(client.generation(), 3);
+    assert_eq!(alive_cells, vec![2 * 8 + 3, 3 * 8 + 3, 4 * 8 + 3]);
+}
+
+/// Expected behaviour of a pattern of the library
+enum Behaviour {
+    /// Still life, oscillator or spaceship, with its period and
+    /// its displacement over a period (in absolute values)
+    Periodic(PatternKind, u32, (i32, i32)),
+    /// Dies out at the specified generation
+    Vanishes(u32),
+    /// Methuselah stabilizing at the specified generation, with the
+    /// specified population (gliders included)
+    Stabilizes(u32, u32),
+    /// Methuselah not stabilized after the specified generation
+    Unstable(u32),
+    /// Emits a glider at each period, its own cells repeating
+    Gun(u32),
+}
+
+fn behaviour(pattern_name: &str) -> Behaviour {
+    use Behaviour::*;
+    use PatternKind::*;
+
+    match pattern_name {
+        "Block" | "Bee hive" | "Loaf" | "Boat" | "Tub" => Periodic(StillLife, 1, (0, 0)),
+        "Blinker" | "Toad" | "Beacon" => Periodic(Oscillator, 2, (0, 0)),
+        "Pulsar" => Periodic(Oscillator, 3, (0, 0)),
+        "Penta-decathlon" => Periodic(Oscillator, 15, (0, 0)),
+        "Glide spaceship" => Periodic(Spaceship, 4, (1, 1)),
+        "Light-weight spaceship" | "Middle-weight spaceship" | "Heavy-weight spaceship" => {
+            Periodic(Spaceship, 4, (0, 2))
+        }
+        "Diehard" => Vanishes(130),
+        "R-pentomino" => Stabilizes(1103, 116),
+        "Acorn" => Unstable(1000),
+        "Gosper glider gun" => Gun(30),
+        "Simkin glider gun" => Gun(120),
+        _ => panic!("No expected behaviour for {}", pattern_name),
+    }
+}
+
+/// Universe with a pattern at its center, far from the
+/// edges it could wrap around
+fn centered(pattern_name: &str, size: u32) -> Universe {
+    let mut template = Universe::new(64, 64);
+    template.init(patterns::find(pattern_name).unwrap().pattern.to_string());
+    let alive_cells = template
+        .get_cells()
+        .iter()
+        .enumerate()
+        .filter(|(_, &cell)| cell == Cell::Alive)
+        .map(|(idx, _)| (idx as u32 / 64 + size / 2, idx as u32 % 64 + size / 2))
+        .collect();
+
+    let mut universe = Universe::new(size, size);
+    universe.set_cells_alive(alive_cells);
+    universe
+}
+
+fn tick_until(universe: &mut Universe, generation: u32) {
+    while universe.generation() < generation {
+        universe.tick();
+    }
+}
+
+/// Cells of a rectangle of the universe
+fn area(universe: &Universe, bounding_box: &BoundingBox) -> Vec<Cell> {
+    (bounding_box.row..bounding_box.row + bounding_box.height)
+        .flat_map(|row| {
+            (bounding_box.column..bounding_box.column + bounding_box.width)
+                .map(move |column| (row * universe.width() + column) as usize)
+        })
+        .map(|idx| universe.get_cells()[idx])
+        .collect()
+}
+
+#[test]
+pub fn test_pattern_library() {
+    patterns::PATTERNS
+        .iter()
+        .for_each(|pattern| match behaviour(pattern.name) {
+            Behaviour::Periodic(kind, period, (dx, dy)) => {
+                let periodicity = periodicity_of(pattern.name, 50).unwrap();
+                assert_eq!(
+                    (
+                        periodicity.kind,
+                        periodicity.period,
+                        (periodicity.dx.abs(), periodicity.dy.abs())
+                    ),
+                    (kind, period, (dx, dy)),
+                    "{}",
+                    pattern.name
+                );
+            }
+            Behaviour::Vanishes(generation) => {
+                let mut universe = centered(pattern.name, 64);
+                tick_until(&mut universe, generation - 1);
+                assert!(universe.population() > 0, "{}", pattern.name);
+                universe.tick();
+                assert_eq!(universe.population(), 0, "{}", pattern.name);
+            }
+            Behaviour::Stabilizes(generation, population) => {
+                // Large enough for the escaping gliders not to wrap
+                // around and hit the remaining objects
+                let mut universe = centered(pattern.name, 500);
+                tick_until(&mut universe, generation - 1);
+                assert_ne!(universe.population(), population, "{}", pattern.name);
+                [generation, generation + 1, generation + 2]
+                    .iter()
+                    .for_each(|&generation| {
+                        tick_until(&mut universe, generation);
+                        assert_eq!(universe.population(), population, "{}", pattern.name);
+                    });
+            }
+            Behaviour::Unstable(generation) => {
+                let mut universe = centered(pattern.name, 256);
+                (0..generation).for_each(|_| {
+                    universe.tick();
+                    assert_eq!(universe.periodicity(), None, "{}", pattern.name);
+                });
+            }
+            Behaviour::Gun(period) => {
+                let mut universe = Universe::new(128, 128);
+                universe.init(pattern.pattern.to_string());
+                let gun = universe.bounding_box();
+
+                // Once the first glider is away, the gun repeats
+                // itself and the population grows by a glider
+                tick_until(&mut universe, 2 * period);
+                let cells = area(&universe, &gun);
+                let population = universe.population();
+                tick_until(&mut universe, 3 * period);
+                assert_eq!(area(&universe, &gun), cells, "{}", pattern.name);
+                assert_eq!(universe.population(), population + 5, "{}", pattern.name);
+            }
+        });
+}
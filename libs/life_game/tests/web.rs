@@ -0,0 +1,28 @@
+//! Test suite for the Web and headless browsers.
+#![cfg(target_arch = "wasm32")]
+
+extern crate life_game;
+extern crate wasm_bindgen_test;
+
+use life_game::{patterns, Universe};
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+pub fn test_cells_array() {
+    let mut universe = Universe::new(16, 8);
+    universe.init(patterns::find("Glide spaceship").unwrap().pattern.to_string());
+    universe.tick();
+
+    let cells = universe.cells_array();
+    assert_eq!(cells.length() as usize, universe.cells_len());
+    assert_eq!(
+        cells.to_vec(),
+        universe
+            .get_cells()
+            .iter()
+            .map(|&cell| cell as u8)
+            .collect::<Vec<u8>>()
+    );
+}
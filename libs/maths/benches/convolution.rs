@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use maths::{
+    convolution::{correlate, gaussian_kernel, sparse_correlation, SparseKernel},
+    coordinate::Coordinate,
+    matrix::Matrix,
+    random::Random,
+};
+
+/// World of random values, only filled in a square of
+/// `filled` cells at its center
+fn world(size: usize, filled: usize) -> Matrix<f64> {
+    let mut random = Random::new(1);
+    let start = (size - filled) / 2;
+    let inside = |position: usize| (start..start + filled).contains(&position);
+
+    Matrix::from_function(size, size, |x, y| {
+        let value = random.next_f64();
+        if inside(x) && inside(y) {
+            value
+        } else {
+            0.0
+        }
+    })
+}
+
+fn dense_convolution(matrix: &Matrix<f64>, kernel: &Matrix<f64>) -> Matrix<f64> {
+    Matrix::from_function(matrix.width, matrix.height, |x, y| {
+        correlate(&Coordinate(x, y), matrix, kernel)
+    })
+}
+
+fn bench_convolution(c: &mut Criterion) {
+    let kernel = gaussian_kernel(13, 0.5, 0.15);
+    let sparse_kernel = SparseKernel::new(&kernel);
+    let trimmed_kernel = SparseKernel::with_threshold(&kernel, 1e-6);
+
+    let mut group = c.benchmark_group("convolution");
+    group.sample_size(10);
+
+    // A lone creature in a large world, and a world full of matter
+    [("sparse scene", 20), ("dense scene", 128)]
+        .iter()
+        .for_each(|&(scene, filled)| {
+            let matrix = world(128, filled);
+
+            group.bench_with_input(BenchmarkId::new("dense", scene), &matrix, |b, matrix| {
+                b.iter(|| dense_convolution(black_box(matrix), &kernel))
+            });
+            group.bench_with_input(BenchmarkId::new("sparse", scene), &matrix, |b, matrix| {
+                b.iter(|| sparse_correlation(black_box(matrix), &sparse_kernel, 8))
+            });
+            group.bench_with_input(
+                BenchmarkId::new("sparse trimmed", scene),
+                &matrix,
+                |b, matrix| b.iter(|| sparse_correlation(black_box(matrix), &trimmed_kernel, 8)),
+            );
+        });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_convolution);
+criterion_main!(benches);
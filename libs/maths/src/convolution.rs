@@ -1,24 +1,77 @@
 use crate::{
-    coordinate::{toroidal_translation, vector, Coordinate},
+    coordinate::{
+        hex_distance, toroidal_translation, toroidal_translation_3d, vector, vector_3d, Coordinate,
+        Coordinate3, Vector,
+    },
     function::{distance, normal_gauss},
-    matrix::Matrix,
+    matrix::{Matrix, Matrix3},
 };
 
+/// Smoothing 1D kernel, to combine with `DERIVATIVE_KERNEL_1D`
+/// in a separable Sobel filter
+pub const SMOOTHING_KERNEL_1D: [f64; 3] = [0.25, 0.5, 0.25];
+
+/// Central difference 1D kernel: the derivative along its axis
+pub const DERIVATIVE_KERNEL_1D: [f64; 3] = [-0.5, 0.0, 0.5];
+
+/// Center of a kernel, the anchor used by default: `(width / 2, height / 2)`
+pub fn kernel_center(kernel: &Matrix<f64>) -> Coordinate {
+    Coordinate(kernel.width / 2, kernel.height / 2)
+}
+
+/// Correlation of a point of a toroidal matrix by a kernel anchored
+/// at its center: the kernel is applied as is (see `correlate`)
+#[deprecated(note = "this is a correlation, use `correlate` (or `convolve`)")]
 pub fn convolute(point: &Coordinate, matrix: &Matrix<f64>, kernel: &Matrix<f64>) -> f64 {
-    let k_center = kernel.height / 2;
+    correlate(point, matrix, kernel)
+}
+
+/// Correlation of a point of a toroidal matrix by a kernel:
+/// `Σ K(d)·M(point + d)`, `d` being the vector from the kernel center
+pub fn correlate(point: &Coordinate, matrix: &Matrix<f64>, kernel: &Matrix<f64>) -> f64 {
+    correlate_with_anchor(point, matrix, kernel, &kernel_center(kernel))
+}
+
+/// Convolution of a point of a toroidal matrix by a kernel:
+/// `Σ K(d)·M(point - d)`
+///
+/// It is the correlation by the kernel flipped on both axes
+/// (`kernel.flip_horizontal().flip_vertical()`), without copying it:
+/// `d` is then taken from the cell that the flip moves to the center,
+/// `((width - 1) / 2, (height - 1) / 2)`.
+pub fn convolve(point: &Coordinate, matrix: &Matrix<f64>, kernel: &Matrix<f64>) -> f64 {
+    let anchor = Coordinate((kernel.width - 1) / 2, (kernel.height - 1) / 2);
 
+    kernel
+        .iter()
+        .enumerate()
+        .fold(0.0, |result, (k_index, k_coef)| {
+            let k_vector = vector(&kernel.index_to_coordinate(k_index), &anchor);
+            let neighbor_coordinates =
+                toroidal_translation(point, &k_vector, &matrix.width, &matrix.height);
+
+            result + k_coef * matrix.get_by_coordinate(&neighbor_coordinates)
+        })
+}
+
+/// Correlate a point of a toroidal matrix by a kernel of any size
+///
+/// The kernel cell at `anchor` is multiplied by the matrix cell at `point`,
+/// the other kernel cells by the cells at the same vector from `point`:
+/// the kernel is not flipped.
+pub fn correlate_with_anchor(
+    point: &Coordinate,
+    matrix: &Matrix<f64>,
+    kernel: &Matrix<f64>,
+    anchor: &Coordinate,
+) -> f64 {
     kernel
         .iter()
         .enumerate()
         // Get current kernel cell coordinates
         .map(|(k_index, k_val)| (kernel.index_to_coordinate(k_index), k_val))
-        // Get the vector from kernel center to current kernel cell
-        .map(|(k_coordinate, k_val)| {
-            (
-                vector(&Coordinate(k_center, k_center), &k_coordinate),
-                k_val,
-            )
-        })
+        // Get the vector from kernel anchor to current kernel cell
+        .map(|(k_coordinate, k_val)| (vector(anchor, &k_coordinate), k_val))
         .fold(0.0, |result, (k_vector, k_coef)| {
             let neighbor_coordinates =
                 toroidal_translation(point, &k_vector, &matrix.width, &matrix.height);
@@ -29,26 +82,246 @@ pub fn convolute(point: &Coordinate, matrix: &Matrix<f64>, kernel: &Matrix<f64>)
         })
 }
 
+/// Correlation of a whole toroidal matrix by the product of two 1D kernels
+///
+/// The result is the one of `correlate` with the kernel
+/// `K(x, y) = horizontal[x] · vertical[y]`, for `w + h` products
+/// per cell instead of `w · h`. Both kernels are anchored at their
+/// center (`len / 2`) and are not flipped: reverse them for a
+/// convolution.
+pub fn separable_correlation(
+    matrix: &Matrix<f64>,
+    horizontal: &[f64],
+    vertical: &[f64],
+) -> Matrix<f64> {
+    let correlate_axis = |matrix: &Matrix<f64>, kernel: &[f64], axis: fn(isize) -> Vector| {
+        let anchor = (kernel.len() / 2) as isize;
+        Matrix::from_function(matrix.width, matrix.height, |x, y| {
+            kernel
+                .iter()
+                .enumerate()
+                .fold(0.0, |result, (k_index, k_coef)| {
+                    let neighbor_coordinates = toroidal_translation(
+                        &Coordinate(x, y),
+                        &axis(k_index as isize - anchor),
+                        &matrix.width,
+                        &matrix.height,
+                    );
+
+                    result + k_coef * matrix.get_by_coordinate(&neighbor_coordinates)
+                })
+        })
+    };
+
+    let rows = correlate_axis(matrix, horizontal, |delta| Vector(delta, 0));
+    correlate_axis(&rows, vertical, |delta| Vector(0, delta))
+}
+
+/// Normalized 1D gaussian kernel of `2 * radius + 1` values, to blur
+/// a matrix with `separable_correlation`
+pub fn gaussian_kernel_1d(radius: usize, standard_deviation: f64) -> Vec<f64> {
+    let values: Vec<f64> = (0..=2 * radius)
+        .map(|index| normal_gauss(index as f64, radius as f64, standard_deviation))
+        .collect();
+    let sum: f64 = values.iter().sum();
+
+    values.into_iter().map(|value| value / sum).collect()
+}
+
+/// Non-zero coefficients of a convolution kernel, with their
+/// vector from the kernel anchor
+///
+/// Lenia kernels are mostly made of (near) zero coefficients: skipping
+/// them makes the convolution much cheaper than with `correlate`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseKernel {
+    offsets: Vec<(Vector, f64)>,
+    reach: usize,
+}
+
+impl SparseKernel {
+    /// Keep the non-zero coefficients of a kernel
+    ///
+    /// `sparse_correlate` then gives the same results as `correlate`.
+    pub fn new(kernel: &Matrix<f64>) -> Self {
+        Self::with_threshold(kernel, 0.0)
+    }
+
+    /// Keep the non-zero coefficients of a kernel flipped on both axes:
+    /// `sparse_correlate` then gives the results of `convolve`
+    /// instead of `correlate`
+    pub fn flipped(kernel: &Matrix<f64>) -> Self {
+        Self::new(&kernel.flip_horizontal().flip_vertical())
+    }
+
+    /// Keep the coefficients of a kernel whose absolute value is
+    /// greater than `threshold`
+    pub fn with_threshold(kernel: &Matrix<f64>, threshold: f64) -> Self {
+        Self::with_anchor(kernel, &kernel_center(kernel), threshold)
+    }
+
+    /// Keep the coefficients of a kernel whose absolute value is
+    /// greater than `threshold`, for a convolution anchored at
+    /// `anchor` (see `correlate_with_anchor`)
+    pub fn with_anchor(kernel: &Matrix<f64>, anchor: &Coordinate, threshold: f64) -> Self {
+        let offsets: Vec<(Vector, f64)> = kernel
+            .iter()
+            .enumerate()
+            .filter(|(_, k_coef)| k_coef.abs() > threshold)
+            .map(|(k_index, k_coef)| {
+                let k_coordinate = kernel.index_to_coordinate(k_index);
+                (vector(anchor, &k_coordinate), *k_coef)
+            })
+            .collect();
+        let reach = offsets
+            .iter()
+            .map(|(Vector(x, y), _)| x.unsigned_abs().max(y.unsigned_abs()))
+            .max()
+            .unwrap_or(0);
+
+        Self { offsets, reach }
+    }
+
+    /// Number of coefficients kept
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+
+    /// Largest distance (along an axis) between the
+    /// kernel anchor and a coefficient kept
+    pub fn reach(&self) -> usize {
+        self.reach
+    }
+}
+
+/// Correlation of a point of a toroidal matrix by a sparse kernel: the
+/// sparse equivalent of `correlate`, only the non-zero coefficients of
+/// the kernel are multiplied
+///
+/// The kernel is applied as is: build it with `SparseKernel::flipped`
+/// to get the result of `convolve` instead.
+pub fn sparse_correlate(point: &Coordinate, matrix: &Matrix<f64>, kernel: &SparseKernel) -> f64 {
+    kernel
+        .offsets
+        .iter()
+        .fold(0.0, |result, (k_vector, k_coef)| {
+            let neighbor_coordinates =
+                toroidal_translation(point, k_vector, &matrix.width, &matrix.height);
+
+            result + k_coef * matrix.get_by_coordinate(&neighbor_coordinates)
+        })
+}
+
+/// Correlation of a whole toroidal matrix by a sparse kernel
+///
+/// The matrix is split in square tiles of `tile_size` cells. Cells of a
+/// tile out of the kernel reach of any non-empty tile are left to 0
+/// without being computed. The result is the same as calling
+/// `sparse_correlate` on each cell, so it is a convolution for a kernel
+/// built with `SparseKernel::flipped`.
+pub fn sparse_correlation(
+    matrix: &Matrix<f64>,
+    kernel: &SparseKernel,
+    tile_size: usize,
+) -> Matrix<f64> {
+    let columns = matrix.width.div_ceil(tile_size);
+    let active = active_tiles(matrix, kernel, tile_size);
+
+    Matrix::from_function(matrix.width, matrix.height, |x, y| {
+        if active[(y / tile_size) * columns + x / tile_size] {
+            sparse_correlate(&Coordinate(x, y), matrix, kernel)
+        } else {
+            0.0
+        }
+    })
+}
+
+/// Denote, row by row, if each tile of `sparse_correlation` is in the
+/// kernel reach of a non-empty tile, and so must be computed
+fn active_tiles(matrix: &Matrix<f64>, kernel: &SparseKernel, tile_size: usize) -> Vec<bool> {
+    let columns = matrix.width.div_ceil(tile_size);
+    let rows = matrix.height.div_ceil(tile_size);
+
+    // Denote if each tile holds some mass
+    let mut filled = vec![false; columns * rows];
+    matrix.iter().enumerate().for_each(|(index, value)| {
+        if *value != 0.0 {
+            let Coordinate(x, y) = matrix.index_to_coordinate(index);
+            filled[(y / tile_size) * columns + x / tile_size] = true;
+        }
+    });
+
+    // Tiles of an axis that a tile can read through the kernel
+    let reachable = |tiles: usize, size: usize| -> Vec<Vec<usize>> {
+        (0..tiles)
+            .map(|tile| {
+                let start = (tile * tile_size) as isize - kernel.reach as isize;
+                let end = ((tile + 1) * tile_size).min(size) as isize + kernel.reach as isize;
+                let mut reached: Vec<usize> = (start..end)
+                    .map(|position| position.rem_euclid(size as isize) as usize / tile_size)
+                    .collect();
+                reached.sort_unstable();
+                reached.dedup();
+                reached
+            })
+            .collect()
+    };
+    let reachable_columns = reachable(columns, matrix.width);
+    let reachable_rows = reachable(rows, matrix.height);
+
+    (0..rows * columns)
+        .map(|tile| {
+            reachable_rows[tile / columns].iter().any(|row| {
+                reachable_columns[tile % columns]
+                    .iter()
+                    .any(|column| filled[row * columns + column])
+            })
+        })
+        .collect()
+}
+
 /// Generate a normalized gaussian kernel
 ///
 /// A normalized Gaussian kernel is a two-dimensional matrix representing
 /// a Gaussian distribution. The Gaussian kernel is often used as a smoothing filter in image
 /// processing and computer vision.
 pub fn gaussian_kernel(radius: usize, mean: f64, standard_deviation: f64) -> Matrix<f64> {
-    let mut kernel = distance_kernel(radius);
+    gaussian_shell(distance_kernel(radius), mean, standard_deviation)
+}
+
+/// Generate a normalized gaussian kernel for a hexagonal grid
+/// stored in axial coordinates
+///
+/// It is the hexagonal equivalent of `gaussian_kernel`, to compare the
+/// behaviour of Lenia creatures on both lattices.
+pub fn hex_gaussian_kernel(radius: usize, mean: f64, standard_deviation: f64) -> Matrix<f64> {
+    gaussian_shell(hex_distance_kernel(radius), mean, standard_deviation)
+}
 
+/// Turn a distance kernel into a normalized gaussian kernel
+fn gaussian_shell(mut kernel: Matrix<f64>, mean: f64, standard_deviation: f64) -> Matrix<f64> {
+    gaussian_shell_values(&mut kernel.m, mean, standard_deviation);
+
+    kernel
+}
+
+/// Turn the values of a distance kernel into the values
+/// of a normalized gaussian kernel
+fn gaussian_shell_values(values: &mut [f64], mean: f64, standard_deviation: f64) {
     // Turn distance kernel into gaussian kernel
-    kernel.iter_mut().for_each(|val| {
+    values.iter_mut().for_each(|val| {
         if *val != 0.0 {
             *val = normal_gauss(*val, mean, standard_deviation)
         }
     });
 
     // Normalize the kernel
-    let sum = kernel.iter().fold(0.0, |sum, val| sum + val);
-    kernel.iter_mut().for_each(|val| *val = *val / sum);
-
-    kernel
+    let sum = values.iter().fold(0.0, |sum, val| sum + val);
+    values.iter_mut().for_each(|val| *val /= sum);
 }
 
 /// Generate a normalized distance kernel of the specified radius
@@ -67,12 +340,182 @@ pub fn distance_kernel(radius: usize) -> Matrix<f64> {
         distance((x as f64, y as f64), (center, center)) / radius as f64)
 }
 
+/// Cell of distance 0 of the kernels built from `distance_kernel` or
+/// `hex_distance_kernel`: `((width - 1) / 2, (height - 1) / 2)`
+///
+/// It differs from `kernel_center` for kernels of even sizes, like the
+/// ones of `distance_kernel`.
+pub fn distance_kernel_center(kernel: &Matrix<f64>) -> Coordinate {
+    Coordinate((kernel.width - 1) / 2, (kernel.height - 1) / 2)
+}
+
+/// Generate a normalized hexagonal distance kernel of the specified radius
+///
+/// The kernel is a squared matrix of size `radius * 2 + 1`, for a hexagonal
+/// grid stored in axial coordinates (see `coordinate::HEX_DIRECTIONS`).
+/// Each element is the number of steps from the center divided by `radius`.
+pub fn hex_distance_kernel(radius: usize) -> Matrix<f64> {
+    let diameter = radius * 2 + 1;
+
+    Matrix::from_function(diameter, diameter, |x, y| {
+        let vector = Vector(x as isize - radius as isize, y as isize - radius as isize);
+        hex_distance(&vector) as f64 / radius as f64
+    })
+}
+
+/// 3 dimensions equivalent of `convolute`, in a toroidal space
+#[deprecated(note = "this is a correlation, use `correlate_3d` (or `convolve_3d`)")]
+pub fn convolute_3d(point: &Coordinate3, matrix: &Matrix3<f64>, kernel: &Matrix3<f64>) -> f64 {
+    correlate_3d(point, matrix, kernel)
+}
+
+/// 3 dimensions equivalent of `correlate`, in a toroidal space
+pub fn correlate_3d(point: &Coordinate3, matrix: &Matrix3<f64>, kernel: &Matrix3<f64>) -> f64 {
+    let center = Coordinate3(kernel.width / 2, kernel.height / 2, kernel.depth / 2);
+
+    kernel
+        .iter()
+        .enumerate()
+        .filter(|(_, k_coef)| **k_coef != 0.0)
+        .fold(0.0, |result, (k_index, k_coef)| {
+            let k_vector = vector_3d(&center, &kernel.index_to_coordinate(k_index));
+            let neighbor_coordinates = toroidal_translation_3d(
+                point,
+                &k_vector,
+                &matrix.width,
+                &matrix.height,
+                &matrix.depth,
+            );
+
+            result + k_coef * matrix.get_by_coordinate(&neighbor_coordinates)
+        })
+}
+
+/// 3 dimensions equivalent of `convolve`: the correlation by the
+/// kernel flipped on the 3 axes, without copying it
+pub fn convolve_3d(point: &Coordinate3, matrix: &Matrix3<f64>, kernel: &Matrix3<f64>) -> f64 {
+    let anchor = Coordinate3(
+        (kernel.width - 1) / 2,
+        (kernel.height - 1) / 2,
+        (kernel.depth - 1) / 2,
+    );
+
+    kernel
+        .iter()
+        .enumerate()
+        .filter(|(_, k_coef)| **k_coef != 0.0)
+        .fold(0.0, |result, (k_index, k_coef)| {
+            let k_vector = vector_3d(&kernel.index_to_coordinate(k_index), &anchor);
+            let neighbor_coordinates = toroidal_translation_3d(
+                point,
+                &k_vector,
+                &matrix.width,
+                &matrix.height,
+                &matrix.depth,
+            );
+
+            result + k_coef * matrix.get_by_coordinate(&neighbor_coordinates)
+        })
+}
+
+/// Generate a normalized 3 dimensions distance kernel of the specified radius
+///
+/// The kernel is a cube of size `radius * 2 + 1`. Each element is the
+/// distance of the corresponding point from the center, divided by `radius`.
+pub fn distance_kernel_3d(radius: usize) -> Matrix3<f64> {
+    let diameter = radius * 2 + 1;
+    let offset = |position: usize| position as f64 - radius as f64;
+
+    Matrix3::from_function(diameter, diameter, diameter, |x, y, z| {
+        (offset(x).powi(2) + offset(y).powi(2) + offset(z).powi(2)).sqrt() / radius as f64
+    })
+}
+
+/// Generate a normalized 3 dimensions gaussian kernel
+///
+/// It is the spherical equivalent of `gaussian_kernel`. The corners of the
+/// cube, further than `radius` from the center, are cut off.
+pub fn gaussian_kernel_3d(radius: usize, mean: f64, standard_deviation: f64) -> Matrix3<f64> {
+    let mut kernel = distance_kernel_3d(radius);
+    kernel.iter_mut().for_each(|val| {
+        if *val > 1.0 {
+            *val = 0.0
+        }
+    });
+    gaussian_shell_values(&mut kernel.m, mean, standard_deviation);
+
+    kernel
+}
+
+/// Radial profile of a kernel: its value as a function of the
+/// distance from `center`, divided by `radius`
+///
+/// Return a `(distance, value)` point for each distinct distance, sorted
+/// by distance. Cells at the same distance are averaged.
+pub fn radial_profile(kernel: &Matrix<f64>, center: &Coordinate, radius: f64) -> Vec<(f64, f64)> {
+    let mut points: Vec<(f64, f64)> = kernel
+        .iter()
+        .enumerate()
+        .map(|(k_index, k_val)| {
+            let Coordinate(x, y) = kernel.index_to_coordinate(k_index);
+            let center = (center.0 as f64, center.1 as f64);
+            (distance((x as f64, y as f64), center) / radius, *k_val)
+        })
+        .collect();
+    points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    // Average the values of the cells at the same distance
+    let mut profile: Vec<(f64, f64, usize)> = Vec::new();
+    points
+        .into_iter()
+        .for_each(|(distance, value)| match profile.last_mut() {
+            Some((last_distance, sum, count)) if distance - *last_distance < 1e-9 => {
+                *sum += value;
+                *count += 1;
+            }
+            _ => profile.push((distance, value, 1)),
+        });
+
+    profile
+        .into_iter()
+        .map(|(distance, sum, count)| (distance, sum / count as f64))
+        .collect()
+}
+
+/// Cross-section of a kernel: the values of its row through `center`
+pub fn cross_section(kernel: &Matrix<f64>, center: &Coordinate) -> Result<Vec<f64>, String> {
+    if center.1 >= kernel.height {
+        return Err(format!(
+            "Row {} is out of a kernel of height {}",
+            center.1, kernel.height
+        ));
+    }
+
+    let start = kernel.coordinate_to_index(&Coordinate(0, center.1));
+    Ok(kernel.m[start..start + kernel.width].to_vec())
+}
+
+/// Growth function sampled at `samples` regularly spaced
+/// potentials over [0, 1], bounds included
+///
+/// Return the `(potential, growth)` points.
+pub fn growth_curve(growth_function: fn(&f64) -> f64, samples: usize) -> Vec<(f64, f64)> {
+    (0..samples)
+        .map(|sample| {
+            let potential = sample as f64 / (samples.max(2) - 1) as f64;
+            (potential, growth_function(&potential))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::random::Random;
+    use proptest::prelude::*;
 
     #[test]
-    fn test_convolute() {
+    fn test_convolve() {
         let input = Matrix::from_vec(
             vec![
                 vec![1.0, 2.0, 3.0],
@@ -87,7 +530,7 @@ mod test {
         )
         .unwrap();
 
-        let mut kernel = Matrix::from_vec(
+        let kernel = Matrix::from_vec(
             vec![
                 vec![0.0, 1.0, 0.0],
                 vec![1.0, -4.0, 1.0],
@@ -101,12 +544,6 @@ mod test {
         )
         .unwrap();
 
-        // Reverse kernel before convolution
-        kernel.m.reverse();
-
-        println!("input: {:?}", input);
-        println!("kernel: {:?}", kernel);
-
         // Note : expected result is the result of the
         // original python code:
         //
@@ -146,14 +583,14 @@ mod test {
             .iter()
             .enumerate()
             .map(|(index, _)| input.index_to_coordinate(index))
-            // Convolution
-            .map(|point| convolute(&point, &input, &kernel))
+            .map(|point| convolve(&point, &input, &kernel))
             .collect();
 
         assert_eq!(Matrix::from_vec(result, 3, 3).unwrap(), expected_result);
     }
 
     #[test]
+    #[allow(clippy::approx_constant)]
     fn test_distance_kernel() {
         // Note : expected result is the result of the
         // original python code:
@@ -248,4 +685,387 @@ mod test {
             Matrix::from_vec(expected_result, 4, 4).unwrap()
         );
     }
+
+    #[test]
+    fn test_hex_distance_kernel() {
+        let expected_result: Vec<f64> = vec![
+            vec![1.0, 1.0, 2.0],
+            vec![1.0, 0.0, 1.0],
+            vec![2.0, 1.0, 1.0],
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        assert_eq!(
+            hex_distance_kernel(1),
+            Matrix::from_vec(expected_result, 3, 3).unwrap()
+        );
+        // Each ring of the hexagonal grid holds 6 cells per step
+        let kernel = hex_distance_kernel(3);
+        let ring_size = |distance: f64| kernel.iter().filter(|&&val| val == distance).count();
+        assert_eq!(ring_size(1.0 / 3.0), 6);
+        assert_eq!(ring_size(2.0 / 3.0), 12);
+        assert_eq!(ring_size(1.0), 18);
+    }
+
+    #[test]
+    fn test_hex_gaussian_kernel() {
+        let kernel = hex_gaussian_kernel(4, 0.5, 0.15);
+
+        assert!((kernel.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(*kernel.get_by_coordinate(&Coordinate(4, 4)), 0.0);
+        // The kernel has the 6-fold symmetry of the grid
+        assert_eq!(
+            kernel.get_by_coordinate(&Coordinate(6, 4)),
+            kernel.get_by_coordinate(&Coordinate(6, 6))
+        );
+        assert_eq!(
+            kernel.get_by_coordinate(&Coordinate(6, 4)),
+            kernel.get_by_coordinate(&Coordinate(2, 2))
+        );
+    }
+
+    #[test]
+    fn test_correlate_3d() {
+        let matrix = Matrix3::from_function(4, 3, 5, |x, y, z| (x + 4 * y + 12 * z) as f64);
+
+        // Convolution by a shifted delta kernel translates the matrix
+        let mut kernel = Matrix3::from_constant(3, 3, 3, 0.0);
+        kernel.set(&Coordinate3(2, 1, 0), 1.0);
+
+        matrix.iter().enumerate().for_each(|(index, _)| {
+            let Coordinate3(x, y, z) = matrix.index_to_coordinate(index);
+            let shifted = Coordinate3((x + 1) % 4, y, (z + 4) % 5);
+            let opposite = Coordinate3((x + 3) % 4, y, (z + 1) % 5);
+
+            assert_eq!(
+                correlate_3d(&Coordinate3(x, y, z), &matrix, &kernel),
+                *matrix.get_by_coordinate(&shifted)
+            );
+            // The convolution flips the kernel: the shift is reversed
+            assert_eq!(
+                convolve_3d(&Coordinate3(x, y, z), &matrix, &kernel),
+                *matrix.get_by_coordinate(&opposite)
+            );
+        });
+    }
+
+    #[test]
+    fn test_gaussian_kernel_3d() {
+        let distances = distance_kernel_3d(2);
+        assert_eq!(
+            (distances.width, distances.height, distances.depth),
+            (5, 5, 5)
+        );
+        assert_eq!(*distances.get_by_coordinate(&Coordinate3(2, 2, 2)), 0.0);
+        assert_eq!(*distances.get_by_coordinate(&Coordinate3(2, 2, 0)), 1.0);
+        assert_eq!(
+            *distances.get_by_coordinate(&Coordinate3(4, 4, 4)),
+            3.0_f64.sqrt()
+        );
+
+        let kernel = gaussian_kernel_3d(4, 0.5, 0.15);
+        assert!((kernel.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(*kernel.get_by_coordinate(&Coordinate3(4, 4, 4)), 0.0);
+        assert_eq!(*kernel.get_by_coordinate(&Coordinate3(0, 0, 0)), 0.0);
+        // The kernel is spherical
+        assert_eq!(
+            kernel.get_by_coordinate(&Coordinate3(6, 4, 4)),
+            kernel.get_by_coordinate(&Coordinate3(4, 4, 2))
+        );
+        assert_eq!(
+            kernel.get_by_coordinate(&Coordinate3(5, 6, 4)),
+            kernel.get_by_coordinate(&Coordinate3(4, 3, 2))
+        );
+    }
+
+    #[test]
+    fn test_sparse_kernel() {
+        let kernel = gaussian_kernel_3d(2, 0.5, 0.15).slice_z(2);
+        let sparse = SparseKernel::new(&kernel);
+
+        // Only the 12 cells at a distance of 1 or 2 from the center are kept
+        assert_eq!(sparse.len(), 12);
+        assert_eq!(sparse.reach(), 2);
+        assert!(SparseKernel::new(&Matrix::from_constant(3, 3, 0.0)).is_empty());
+
+        let trimmed = SparseKernel::with_threshold(&gaussian_kernel(4, 0.5, 0.15), 1e-6);
+        assert!(trimmed.len() < 64);
+        assert_eq!(trimmed.reach(), 4);
+    }
+
+    #[test]
+    fn test_sparse_correlation() {
+        let kernel = gaussian_kernel(4, 0.5, 0.15);
+        let sparse = SparseKernel::new(&kernel);
+
+        // Mostly empty matrix: two blobs, one across the edges
+        let matrix = Matrix::from_function(37, 30, |x, y| {
+            let blob = |center_x: f64, center_y: f64| {
+                normal_gauss(
+                    distance((x as f64, y as f64), (center_x, center_y)),
+                    0.0,
+                    1.5,
+                )
+            };
+            let value = blob(10.0, 12.0) + blob(0.0, 29.0);
+            if value > 0.01 {
+                value
+            } else {
+                0.0
+            }
+        });
+
+        let dense = Matrix::from_function(37, 30, |x, y| {
+            correlate(&Coordinate(x, y), &matrix, &kernel)
+        });
+
+        [1, 4, 8, 16, 64].iter().for_each(|&tile_size| {
+            assert_eq!(sparse_correlation(&matrix, &sparse, tile_size), dense);
+        });
+        // Far from the blobs, cells are not computed: with tiles of 4 cells,
+        // in 10 columns and 8 rows, the tile holding (17, 25) is skipped
+        let active = active_tiles(&matrix, &sparse, 4);
+        assert_eq!(active.len(), 10 * 8);
+        assert!(!active[(25 / 4) * 10 + 17 / 4]);
+        assert_eq!(*dense.get_by_coordinate(&Coordinate(17, 25)), 0.0);
+        // Tiles holding a blob are computed
+        assert!(active[(12 / 4) * 10 + 10 / 4]);
+        assert_eq!(
+            sparse_correlate(&Coordinate(10, 9), &matrix, &sparse),
+            *dense.get_by_coordinate(&Coordinate(10, 9))
+        );
+    }
+
+    #[test]
+    fn test_correlate_and_convolve() {
+        let mut random = Random::new(5);
+        let matrix = Matrix::from_function(7, 6, |_, _| random.next_f64());
+
+        // Odd, even and rectangular kernels
+        [(3, 3), (4, 4), (2, 5)]
+            .iter()
+            .for_each(|&(width, height)| {
+                let kernel = Matrix::from_function(width, height, |_, _| random.next_f64() - 0.5);
+                let flipped = kernel.flip_horizontal().flip_vertical();
+
+                (0..matrix.m.len()).for_each(|index| {
+                    let point = matrix.index_to_coordinate(index);
+                    assert!(
+                        (convolve(&point, &matrix, &kernel) - correlate(&point, &matrix, &flipped))
+                            .abs()
+                            < 1e-12
+                    );
+                    assert_eq!(
+                        sparse_correlate(&point, &matrix, &SparseKernel::flipped(&kernel)),
+                        correlate(&point, &matrix, &flipped)
+                    );
+                });
+            });
+
+        // A shift kernel: the correlation reads the next cell,
+        // the convolution the previous one
+        let shift = Matrix::from_vec(vec![0.0, 0.0, 1.0], 3, 1).unwrap();
+        let point = Coordinate(3, 2);
+        assert_eq!(
+            correlate(&point, &matrix, &shift),
+            *matrix.get_by_coordinate(&Coordinate(4, 2))
+        );
+        assert_eq!(
+            convolve(&point, &matrix, &shift),
+            *matrix.get_by_coordinate(&Coordinate(2, 2))
+        );
+    }
+
+    #[test]
+    fn test_rectangular_kernel() {
+        let matrix = Matrix::from_function(5, 4, |x, y| (x + 5 * y) as f64);
+        let kernel = Matrix::from_vec(vec![1.0, 2.0, 3.0], 3, 1).unwrap();
+
+        // Anchored at its center (1, 0)
+        assert_eq!(
+            correlate(&Coordinate(0, 2), &matrix, &kernel),
+            14.0 + 2.0 * 10.0 + 3.0 * 11.0
+        );
+        // Anchored at its first cell
+        assert_eq!(
+            correlate_with_anchor(&Coordinate(4, 1), &matrix, &kernel, &Coordinate(0, 0)),
+            9.0 + 2.0 * 5.0 + 3.0 * 6.0
+        );
+
+        let column = Matrix::from_vec(vec![1.0, 2.0], 1, 2).unwrap();
+        assert_eq!(
+            correlate_with_anchor(&Coordinate(2, 0), &matrix, &column, &Coordinate(0, 1)),
+            17.0 + 2.0 * 2.0
+        );
+
+        let sparse = SparseKernel::with_anchor(&kernel, &Coordinate(2, 0), 0.0);
+        assert_eq!(sparse.reach(), 2);
+        assert_eq!(
+            sparse_correlate(&Coordinate(1, 3), &matrix, &sparse),
+            correlate_with_anchor(&Coordinate(1, 3), &matrix, &kernel, &Coordinate(2, 0))
+        );
+    }
+
+    #[test]
+    fn test_separable_correlation() {
+        let mut random = Random::new(3);
+        let matrix = Matrix::from_function(9, 7, |_, _| random.next_f64());
+        let horizontal = [0.5, -1.0, 2.0];
+        let vertical = [1.0, 0.0, 3.0, -2.0, 0.25];
+
+        let kernel = Matrix::from_function(3, 5, |x, y| horizontal[x] * vertical[y]);
+        let expected =
+            Matrix::from_function(9, 7, |x, y| correlate(&Coordinate(x, y), &matrix, &kernel));
+
+        separable_correlation(&matrix, &horizontal, &vertical)
+            .iter()
+            .zip(expected.iter())
+            .for_each(|(value, expected)| assert!((value - expected).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_blur_and_gradient() {
+        let blur = gaussian_kernel_1d(3, 1.0);
+        assert_eq!(blur.len(), 7);
+        assert!((blur.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert_eq!(blur[0], blur[6]);
+
+        // A blurred constant field does not change
+        let constant = Matrix::from_constant(8, 8, 0.5);
+        separable_correlation(&constant, &blur, &blur)
+            .iter()
+            .for_each(|value| assert!((value - 0.5).abs() < 1e-12));
+
+        // Sobel filter of a ramp along x: the slope, but at the wrapping edges
+        let ramp = Matrix::from_function(8, 6, |x, _| 0.25 * x as f64);
+        let gradient_x = separable_correlation(&ramp, &DERIVATIVE_KERNEL_1D, &SMOOTHING_KERNEL_1D);
+        let gradient_y = separable_correlation(&ramp, &SMOOTHING_KERNEL_1D, &DERIVATIVE_KERNEL_1D);
+        (1..7).for_each(|x| {
+            assert!((gradient_x.get_by_coordinate(&Coordinate(x, 2)) - 0.25).abs() < 1e-12);
+            assert_eq!(*gradient_y.get_by_coordinate(&Coordinate(x, 2)), 0.0);
+        });
+    }
+
+    #[test]
+    fn test_radial_profile() {
+        let kernel = gaussian_kernel(4, 0.5, 0.15);
+        let center = distance_kernel_center(&kernel);
+        assert_eq!(center, Coordinate(3, 3));
+        assert_eq!(kernel_center(&kernel), Coordinate(4, 4));
+        assert_eq!(
+            distance_kernel_center(&hex_distance_kernel(3)),
+            Coordinate(3, 3)
+        );
+        let profile = radial_profile(&kernel, &center, 4.0);
+
+        // Distances 0, 1, √2, 2, √5... divided by the radius
+        assert_eq!(profile[0], (0.0, 0.0));
+        assert_eq!(profile[1].0, 0.25);
+        assert_eq!(profile[2].0, 2.0_f64.sqrt() / 4.0);
+        assert!(profile.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+        // The shell peaks at its mean distance
+        let peak = profile.iter().max_by(|a, b| a.1.total_cmp(&b.1)).unwrap();
+        assert_eq!(peak.0, 0.5);
+        assert_eq!(peak.1, *kernel.get_by_coordinate(&Coordinate(5, 3)));
+
+        let section = cross_section(&kernel, &center).unwrap();
+        assert_eq!(section.len(), 8);
+        assert_eq!(section[3], 0.0);
+        assert_eq!(section[1], section[5]);
+        assert!(cross_section(&kernel, &Coordinate(0, 8)).is_err());
+    }
+
+    #[test]
+    fn test_growth_curve() {
+        let curve = growth_curve(|x| normal_gauss(*x, 0.15, 0.015) * 2.0 - 1.0, 101);
+
+        assert_eq!(curve.len(), 101);
+        assert_eq!(curve[0].0, 0.0);
+        assert_eq!(curve[100].0, 1.0);
+        assert_eq!(curve[15], (0.15, 1.0));
+        assert!((curve[100].1 + 1.0).abs() < 1e-12);
+    }
+
+    /// Matrices of 1 to `max_size` cells wide and high,
+    /// with values in [-10, 10]
+    fn matrix_strategy(max_size: usize) -> impl Strategy<Value = Matrix<f64>> {
+        (1..=max_size, 1..=max_size).prop_flat_map(|(width, height)| {
+            prop::collection::vec(-10.0..10.0f64, width * height)
+                .prop_map(move |m| Matrix::from_vec(m, width, height).unwrap())
+        })
+    }
+
+    /// Square kernels of odd size, up to 7 cells wide
+    fn kernel_strategy() -> impl Strategy<Value = Matrix<f64>> {
+        (0..4usize).prop_flat_map(|radius| {
+            let size = 2 * radius + 1;
+            prop::collection::vec(-1.0..1.0f64, size * size)
+                .prop_map(move |m| Matrix::from_vec(m, size, size).unwrap())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_delta_kernel_identity(matrix in matrix_strategy(12), radius in 0..4usize) {
+            let size = 2 * radius + 1;
+            let delta = Matrix::from_function(size, size, |x, y| {
+                if (x, y) == (radius, radius) { 1.0 } else { 0.0 }
+            });
+
+            matrix.iter().enumerate().try_for_each(|(index, value)| {
+                let coordinate = matrix.index_to_coordinate(index);
+                prop_assert_eq!(correlate(&coordinate, &matrix, &delta), *value);
+                Ok(())
+            })?;
+            prop_assert_eq!(sparse_correlation(&matrix, &SparseKernel::new(&delta), 4), matrix);
+        }
+
+        #[test]
+        fn prop_convolution_linearity(
+            (first, second) in matrix_strategy(10).prop_flat_map(|first| {
+                let (width, height) = (first.width, first.height);
+                (
+                    Just(first),
+                    prop::collection::vec(-10.0..10.0f64, width * height)
+                        .prop_map(move |m| Matrix::from_vec(m, width, height).unwrap()),
+                )
+            }),
+            kernel in kernel_strategy(),
+            a in -5.0..5.0f64,
+            b in -5.0..5.0f64,
+        ) {
+            let combination = Matrix::from_vec(
+                first.iter().zip(second.iter()).map(|(x, y)| a * x + b * y).collect(),
+                first.width,
+                first.height,
+            )
+            .unwrap();
+
+            (0..combination.m.len()).try_for_each(|index| {
+                let coordinate = combination.index_to_coordinate(index);
+                let expected = a * correlate(&coordinate, &first, &kernel)
+                    + b * correlate(&coordinate, &second, &kernel);
+                let result = correlate(&coordinate, &combination, &kernel);
+                prop_assert!((result - expected).abs() < 1e-9, "{} != {}", result, expected);
+                Ok(())
+            })?;
+        }
+
+        #[test]
+        fn prop_normalized_kernels(
+            radius in 1..16usize,
+            mean in 0.1..0.9f64,
+            standard_deviation in 0.05..0.5f64,
+        ) {
+            let sum: f64 = gaussian_kernel(radius, mean, standard_deviation).iter().sum();
+            prop_assert!((sum - 1.0).abs() < 1e-9, "sum: {}", sum);
+            let sum: f64 = hex_gaussian_kernel(radius, mean, standard_deviation).iter().sum();
+            prop_assert!((sum - 1.0).abs() < 1e-9, "sum: {}", sum);
+            let sum: f64 = gaussian_kernel_3d(radius.min(6), mean, standard_deviation).iter().sum();
+            prop_assert!((sum - 1.0).abs() < 1e-9, "sum: {}", sum);
+        }
+    }
 }
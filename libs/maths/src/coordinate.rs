@@ -32,15 +32,133 @@ pub fn toroidal_translation(
     let width = *plan_width as isize;
     let height = *plan_height as isize;
 
-    let result_x = ((*point_x as isize + vector_x + width) % width) as usize;
-    let result_y = ((*point_y as isize + vector_y + height) % height) as usize;
+    let result_x = (*point_x as isize + vector_x).rem_euclid(width) as usize;
+    let result_y = (*point_y as isize + vector_y).rem_euclid(height) as usize;
 
     Coordinate(result_x, result_y)
 }
 
+/// Represents coordinates of a point in a 3 dimensions
+/// space. Coordinates can not be negative
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coordinate3(pub usize, pub usize, pub usize);
+
+/// Represents a 3 dimensions vector. Its component can be negative
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vector3(pub isize, pub isize, pub isize);
+
+/// Determine the vector to go from a source point to
+/// a destination point in a 3 dimensions space
+pub fn vector_3d(source: &Coordinate3, destination: &Coordinate3) -> Vector3 {
+    Vector3(
+        destination.0 as isize - source.0 as isize,
+        destination.1 as isize - source.1 as isize,
+        destination.2 as isize - source.2 as isize,
+    )
+}
+
+/// Apply a translation of a point by a vector in a
+/// toroidal (periodic) 3 dimensions space
+pub fn toroidal_translation_3d(
+    point: &Coordinate3,
+    vector: &Vector3,
+    space_width: &usize,
+    space_height: &usize,
+    space_depth: &usize,
+) -> Coordinate3 {
+    let wrap = |position: usize, delta: isize, size: usize| {
+        (position as isize + delta).rem_euclid(size as isize) as usize
+    };
+
+    Coordinate3(
+        wrap(point.0, vector.0, *space_width),
+        wrap(point.1, vector.1, *space_height),
+        wrap(point.2, vector.2, *space_depth),
+    )
+}
+
+/// Vectors to the six neighbours of a cell of a hexagonal grid,
+/// in axial coordinates
+///
+/// In axial coordinates, a hexagonal grid is stored as a regular matrix
+/// whose rows are each shifted by half a cell to the left of the previous
+/// one: the neighbours of a cell are the 8 cells around it, except the
+/// top right and the bottom left ones.
+pub const HEX_DIRECTIONS: [Vector; 6] = [
+    Vector(1, 0),
+    Vector(1, 1),
+    Vector(0, 1),
+    Vector(-1, 0),
+    Vector(-1, -1),
+    Vector(0, -1),
+];
+
+/// Number of steps between two cells of a hexagonal grid,
+/// `vector` being the vector between them in axial coordinates
+pub fn hex_distance(vector: &Vector) -> usize {
+    let Vector(x, y) = vector;
+
+    x.unsigned_abs()
+        .max(y.unsigned_abs())
+        .max((x - y).unsigned_abs())
+}
+
+/// Turn the offset coordinates of a cell of a hexagonal grid into
+/// axial coordinates
+///
+/// In offset coordinates, the odd rows are shifted by half
+/// a cell to the right of the even rows.
+pub fn offset_to_axial(point: &Coordinate) -> Vector {
+    let x = point.0 as isize;
+    let y = point.1 as isize;
+
+    Vector(x + (y + 1).div_euclid(2), y)
+}
+
+/// Turn the axial coordinates of a cell of a hexagonal grid into
+/// offset coordinates (that can be negative)
+pub fn axial_to_offset(point: &Vector) -> Vector {
+    let Vector(x, y) = point;
+
+    Vector(x - (y + 1).div_euclid(2), *y)
+}
+
+/// Denote if the cell of a triangular grid points up
+///
+/// Triangles alternate along rows and columns, the one at the origin
+/// points up. A toroidal triangular grid must have an even width and
+/// height to keep this alternation across its edges.
+pub fn is_upward_triangle(point: &Coordinate) -> bool {
+    (point.0 + point.1).is_multiple_of(2)
+}
+
+/// Vectors to the 3 cells of a triangular grid sharing an edge with a cell
+pub fn triangle_edge_neighbors(upward: bool) -> [Vector; 3] {
+    let vertical = if upward { 1 } else { -1 };
+
+    [Vector(-1, 0), Vector(1, 0), Vector(0, vertical)]
+}
+
+/// Vectors to the 12 cells of a triangular grid sharing a vertex with a cell
+pub fn triangle_vertex_neighbors(upward: bool) -> Vec<Vector> {
+    // Rows above an upward triangle, only touching its top vertex, are
+    // narrower than the rows below it. A downward triangle is the mirror.
+    let direction = if upward { 1 } else { -1 };
+
+    (-1..=1)
+        .flat_map(|delta_row: isize| {
+            let half_width: isize = if delta_row == -1 { 1 } else { 2 };
+            (-half_width..=half_width)
+                .filter(move |&delta_col| delta_row != 0 || delta_col != 0)
+                .map(move |delta_col| Vector(delta_col, delta_row * direction))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_vector() {
@@ -57,7 +175,7 @@ mod tests {
         ];
 
         data.iter().for_each(|(source, destination, result)| {
-            assert_eq!(vector(&source, &destination), *result)
+            assert_eq!(vector(source, destination), *result)
         });
     }
 
@@ -83,7 +201,138 @@ mod tests {
         ];
 
         data.iter().for_each(|(point, vector, result)| {
-            assert_eq!(toroidal_translation(&point, &vector, &10, &10), *result)
+            assert_eq!(toroidal_translation(point, vector, &10, &10), *result)
         });
     }
+
+    #[test]
+    fn test_toroidal_translation_3d() {
+        let data = [
+            (Coordinate3(0, 0, 0), Vector3(1, 2, 3), Coordinate3(1, 2, 3)),
+            (
+                Coordinate3(1, 2, 3),
+                Vector3(-1, -2, -3),
+                Coordinate3(0, 0, 0),
+            ),
+            // Each axis wraps with its own size
+            (
+                Coordinate3(0, 0, 0),
+                Vector3(-1, -1, -1),
+                Coordinate3(9, 7, 4),
+            ),
+            (Coordinate3(9, 7, 4), Vector3(1, 1, 1), Coordinate3(0, 0, 0)),
+            (
+                Coordinate3(0, 0, 0),
+                Vector3(10, -8, 15),
+                Coordinate3(0, 0, 0),
+            ),
+        ];
+
+        data.iter().for_each(|(point, vector, result)| {
+            assert_eq!(toroidal_translation_3d(point, vector, &10, &8, &5), *result)
+        });
+
+        assert_eq!(
+            vector_3d(&Coordinate3(1, 2, 3), &Coordinate3(0, 4, 1)),
+            Vector3(-1, 2, -2)
+        );
+    }
+
+    #[test]
+    fn test_hex_distance() {
+        assert_eq!(hex_distance(&Vector(0, 0)), 0);
+        HEX_DIRECTIONS
+            .iter()
+            .for_each(|direction| assert_eq!(hex_distance(direction), 1));
+        // Top right and bottom left cells are not neighbours
+        assert_eq!(hex_distance(&Vector(1, -1)), 2);
+        assert_eq!(hex_distance(&Vector(-3, 2)), 5);
+        assert_eq!(hex_distance(&Vector(3, 2)), 3);
+    }
+
+    #[test]
+    fn test_offset_axial() {
+        // Neighbours of a cell of an even and of an odd row, in offset coordinates
+        let data = [
+            (
+                Coordinate(2, 2),
+                [(3, 2), (2, 3), (1, 3), (1, 2), (1, 1), (2, 1)],
+            ),
+            (
+                Coordinate(2, 3),
+                [(3, 3), (3, 4), (2, 4), (1, 3), (2, 2), (3, 2)],
+            ),
+        ];
+
+        data.iter().for_each(|(point, neighbors)| {
+            let axial = offset_to_axial(point);
+            assert_eq!(axial_to_offset(&axial), Vector(2, point.1 as isize));
+
+            HEX_DIRECTIONS
+                .iter()
+                .zip(neighbors)
+                .for_each(|(Vector(x, y), (neighbor_x, neighbor_y))| {
+                    assert_eq!(
+                        axial_to_offset(&Vector(axial.0 + x, axial.1 + y)),
+                        Vector(*neighbor_x, *neighbor_y)
+                    )
+                });
+        });
+    }
+
+    #[test]
+    fn test_triangle_neighbors() {
+        assert!(is_upward_triangle(&Coordinate(0, 0)));
+        assert!(!is_upward_triangle(&Coordinate(1, 0)));
+        assert!(!is_upward_triangle(&Coordinate(0, 1)));
+
+        assert_eq!(triangle_edge_neighbors(true)[2], Vector(0, 1));
+        assert_eq!(triangle_edge_neighbors(false)[2], Vector(0, -1));
+
+        let upward = triangle_vertex_neighbors(true);
+        assert_eq!(upward.len(), 12);
+        assert!(upward.contains(&Vector(-2, 1)));
+        assert!(!upward.contains(&Vector(-2, -1)));
+        // Neighbourhoods are symmetric: a cell is the neighbour of its neighbours
+        upward.iter().for_each(|Vector(x, y)| {
+            let neighbor_upward = (x + y).rem_euclid(2) == 0;
+            assert!(triangle_vertex_neighbors(neighbor_upward).contains(&Vector(-x, -y)))
+        });
+    }
+
+    proptest! {
+        #[test]
+        fn prop_toroidal_translation_round_trip(
+            (width, height, x, y) in (1..50usize, 1..50usize)
+                .prop_flat_map(|(width, height)| (Just(width), Just(height), 0..width, 0..height)),
+            vector_x in -200..200isize,
+            vector_y in -200..200isize,
+        ) {
+            let point = Coordinate(x, y);
+            let translated = toroidal_translation(&point, &Vector(vector_x, vector_y), &width, &height);
+
+            prop_assert!(translated.0 < width && translated.1 < height);
+            prop_assert_eq!(
+                toroidal_translation(&translated, &Vector(-vector_x, -vector_y), &width, &height),
+                point
+            );
+        }
+
+        #[test]
+        fn prop_toroidal_translation_3d_round_trip(
+            (size, x, y, z) in (1..20usize)
+                .prop_flat_map(|size| (Just(size), 0..size, 0..size, 0..size)),
+            vector in (-60..60isize, -60..60isize, -60..60isize),
+        ) {
+            let point = Coordinate3(x, y, z);
+            let vector = Vector3(vector.0, vector.1, vector.2);
+            let opposite = Vector3(-vector.0, -vector.1, -vector.2);
+            let translated = toroidal_translation_3d(&point, &vector, &size, &size, &size);
+
+            prop_assert_eq!(
+                toroidal_translation_3d(&translated, &opposite, &size, &size, &size),
+                point
+            );
+        }
+    }
 }
@@ -6,6 +6,18 @@ pub fn normal_gauss(x: f64, mean: f64, standard_deviation: f64) -> f64 {
     gauss(x, 1.0, mean, standard_deviation)
 }
 
+/// Bump rising from 0 at `x = 0` to 1 at `x = 0.5`, then back to 0 at `x = 1`
+///
+/// `exp(4 − 1 / (x·(1 − x)))`, the exponential kernel core of Lenia
+/// (`kn` = 2 in its JSON format). It is 0 outside of ]0, 1[.
+pub fn exponential_bump(x: f64) -> f64 {
+    if x <= 0.0 || x >= 1.0 {
+        0.0
+    } else {
+        (4.0 - 1.0 / (x * (1.0 - x))).exp()
+    }
+}
+
 /// Calculate the distance between two points in a plan
 ///
 /// √[(x2 − x1)2 + (y2 − y1)2]
@@ -25,4 +37,14 @@ mod test {
         assert_eq!(distance((0.0, 0.0), (1.0, 0.0)), 1.0);
         assert_eq!(distance((0.0, 0.0), (1.0, 1.0)), 2.0_f64.sqrt());
     }
+
+    #[test]
+    fn test_exponential_bump() {
+        assert_eq!(exponential_bump(0.5), 1.0);
+        assert_eq!(exponential_bump(0.0), 0.0);
+        assert_eq!(exponential_bump(1.0), 0.0);
+        assert_eq!(exponential_bump(-0.5), 0.0);
+        assert_eq!(exponential_bump(0.3), exponential_bump(0.7));
+        assert!(exponential_bump(0.3) < exponential_bump(0.4));
+    }
 }
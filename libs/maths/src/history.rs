@@ -0,0 +1,126 @@
+use std::collections::VecDeque;
+use std::mem::{size_of, size_of_val};
+
+/// Value whose memory footprint can be estimated
+pub trait Cost {
+    /// Memory used to store the value (in bytes)
+    fn cost(&self) -> usize;
+}
+
+impl<T> Cost for Vec<T> {
+    fn cost(&self) -> usize {
+        size_of::<Self>() + size_of_val(self.as_slice())
+    }
+}
+
+/// Bounded history of the previous states of a simulation
+///
+/// Entries are pushed at each step, and popped to undo it. When the
+/// memory budget is exceeded, the oldest entries are forgotten.
+#[derive(Debug, Clone)]
+pub struct History<T: Cost> {
+    budget: usize,
+    size: usize,
+    entries: VecDeque<T>,
+}
+
+impl<T: Cost> History<T> {
+    /// Instantiate an empty history using at most `budget` bytes
+    pub fn new(budget: usize) -> Self {
+        Self {
+            budget,
+            size: 0,
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Change the memory budget, forgetting the oldest entries if needed
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.enforce_budget();
+    }
+
+    /// Number of steps that can be undone
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Approximate memory used by the history (in bytes)
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Forget every entry
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.size = 0;
+    }
+
+    /// Record the entry of a new step
+    pub fn push(&mut self, entry: T) {
+        self.size += entry.cost();
+        self.entries.push_back(entry);
+        self.enforce_budget();
+    }
+
+    /// Take back the entry of the latest step
+    pub fn pop(&mut self) -> Option<T> {
+        let entry = self.entries.pop_back()?;
+        self.size -= entry.cost();
+        Some(entry)
+    }
+
+    fn enforce_budget(&mut self) {
+        while self.size > self.budget {
+            match self.entries.pop_front() {
+                Some(entry) => self.size -= entry.cost(),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_push_pop() {
+        let mut history = History::new(1024);
+        assert_eq!(history.pop(), None);
+
+        history.push(vec![1, 2]);
+        history.push(vec![3]);
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.pop(), Some(vec![3]));
+        assert_eq!(history.pop(), Some(vec![1, 2]));
+        assert!(history.is_empty());
+        assert_eq!(history.size(), 0);
+    }
+
+    #[test]
+    fn test_budget() {
+        let cost = size_of::<Vec<u32>>() + 2 * size_of::<u32>();
+        let mut history = History::new(2 * cost);
+
+        history.push(vec![1u32, 1]);
+        history.push(vec![2, 2]);
+        history.push(vec![3, 3]);
+        // The oldest entry has been forgotten
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.size(), 2 * cost);
+
+        history.set_budget(cost);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.pop(), Some(vec![3, 3]));
+
+        // A null budget keeps nothing
+        history.set_budget(0);
+        history.push(vec![4, 4]);
+        assert!(history.is_empty());
+    }
+}
@@ -1,4 +1,8 @@
 pub mod convolution;
 pub mod coordinate;
 pub mod function;
+pub mod history;
 pub mod matrix;
+pub mod random;
+pub mod statistics;
+pub mod summed_area;
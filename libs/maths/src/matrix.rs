@@ -1,7 +1,7 @@
 use std::iter::Iterator;
-use std::ops::{Add, AddAssign, Sub};
+use std::ops::{Add, AddAssign, Mul, Sub};
 
-use crate::coordinate::Coordinate;
+use crate::coordinate::{Coordinate, Coordinate3};
 /// Simple 2 dimensions matrix struct
 #[derive(Debug, PartialEq, Clone)]
 pub struct Matrix<T: Copy + Add + Sub<Output = T>> {
@@ -25,9 +25,9 @@ impl<T: Copy + Add + AddAssign + Sub<Output = T>> Matrix<T> {
     /// result of the specified closure
     ///
     /// Closure will be called with the coordinate of each matrix cell
-    pub fn from_function<F>(width: usize, height: usize, f: F) -> Self
+    pub fn from_function<F>(width: usize, height: usize, mut f: F) -> Self
     where
-        F: Fn(usize, usize) -> T,
+        F: FnMut(usize, usize) -> T,
     {
         let mut m = Vec::new();
         (0..height).for_each(|y| (0..width).for_each(|x| m.push(f(x, y))));
@@ -83,10 +83,6 @@ impl<T: Copy + Add + AddAssign + Sub<Output = T>> Matrix<T> {
         y * self.width + x
     }
 
-    pub fn into_iter(self) -> std::vec::IntoIter<T> {
-        self.m.into_iter()
-    }
-
     pub fn iter<'a>(&'a self) -> std::slice::Iter<'a, T> {
         self.m.iter()
     }
@@ -99,11 +95,210 @@ impl<T: Copy + Add + AddAssign + Sub<Output = T>> Matrix<T> {
       let index = self.coordinate_to_index(coordinates);
       self.m[index] = value;
     }
+
+    /// Mirror the matrix along the x axis: the columns are reversed
+    pub fn flip_horizontal(&self) -> Self {
+        Self::from_function(self.width, self.height, |x, y| {
+            *self.get_by_coordinate(&Coordinate(self.width - 1 - x, y))
+        })
+    }
+
+    /// Mirror the matrix along the y axis: the rows are reversed
+    pub fn flip_vertical(&self) -> Self {
+        Self::from_function(self.width, self.height, |x, y| {
+            *self.get_by_coordinate(&Coordinate(x, self.height - 1 - y))
+        })
+    }
+
+    /// Swap the rows and the columns of the matrix
+    pub fn transpose(&self) -> Self {
+        Self::from_function(self.height, self.width, |x, y| {
+            *self.get_by_coordinate(&Coordinate(y, x))
+        })
+    }
+
+    /// Rotate the matrix by a quarter turn, clockwise when
+    /// its rows are displayed from top to bottom
+    pub fn rotate90(&self) -> Self {
+        Self::from_function(self.height, self.width, |x, y| {
+            *self.get_by_coordinate(&Coordinate(y, self.height - 1 - x))
+        })
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Matrix<T> {
+    /// Compute `self + factor·other`, cell by cell
+    ///
+    /// Panics if the matrices do not have the same size.
+    pub fn add_scaled(&self, other: &Matrix<T>, factor: T) -> Self {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "Matrix sizes do not match"
+        );
+
+        Self {
+            width: self.width,
+            height: self.height,
+            m: self
+                .m
+                .iter()
+                .zip(&other.m)
+                .map(|(&a, &b)| a + factor * b)
+                .collect(),
+        }
+    }
+
+    /// Compute `factor·self`, cell by cell
+    pub fn scale(&self, factor: T) -> Self {
+        Self {
+            width: self.width,
+            height: self.height,
+            m: self.m.iter().map(|&a| factor * a).collect(),
+        }
+    }
+}
+
+impl<T: Copy + Add + Sub<Output = T>> IntoIterator for Matrix<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.m.into_iter()
+    }
+}
+
+/// Simple 3 dimensions matrix struct
+///
+/// Values are stored plane by plane (along the depth), each
+/// plane being stored row by row like a `Matrix`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Matrix3<T: Copy + Add + Sub<Output = T>> {
+    pub width: usize,
+    pub height: usize,
+    pub depth: usize,
+    pub m: Vec<T>,
+}
+
+impl<T: Copy + Add + AddAssign + Sub<Output = T>> Matrix3<T> {
+    /// Instantiate a matrix of size `width` * `height` * `depth`
+    /// filled with the specified constant value
+    pub fn from_constant(width: usize, height: usize, depth: usize, constant: T) -> Self {
+        Self {
+            width,
+            height,
+            depth,
+            m: vec![constant; width * height * depth],
+        }
+    }
+
+    /// Instantiate a matrix of size `width` * `height` * `depth`
+    /// filled with the result of the specified closure
+    ///
+    /// Closure will be called with the coordinate of each matrix cell
+    pub fn from_function<F>(width: usize, height: usize, depth: usize, mut f: F) -> Self
+    where
+        F: FnMut(usize, usize, usize) -> T,
+    {
+        let mut m = Vec::with_capacity(width * height * depth);
+        (0..depth).for_each(|z| {
+            (0..height).for_each(|y| (0..width).for_each(|x| m.push(f(x, y, z))))
+        });
+        Self {
+            width,
+            height,
+            depth,
+            m,
+        }
+    }
+
+    /// Instantiate a matrix from a vector of values stored plane by plane
+    pub fn from_vec(data: Vec<T>, width: usize, height: usize, depth: usize) -> Result<Self, String> {
+        if width == 0 || height == 0 || depth == 0 {
+            return Err("Matrix dimensions can not be null".to_string());
+        }
+
+        if data.len() != width * height * depth {
+            return Err("Data size does not match matrix size".to_string());
+        }
+
+        Ok(Self {
+            width,
+            height,
+            depth,
+            m: data,
+        })
+    }
+
+    /// Get the value of a matrix cell by coordinate
+    pub fn get_by_coordinate(&self, coordinate: &Coordinate3) -> &T {
+        &self.m[self.coordinate_to_index(coordinate)]
+    }
+
+    /// Get the value of a matrix cell by index
+    pub fn get_by_index(&self, index: usize) -> &T {
+        &self.m[index]
+    }
+
+    /// Turn an index into the equivalent coordinate for the current matrix
+    pub fn index_to_coordinate(&self, index: usize) -> Coordinate3 {
+        let plane = self.width * self.height;
+
+        Coordinate3(
+            index % self.width,
+            index % plane / self.width,
+            index / plane,
+        )
+    }
+
+    /// Turn a coordinate into the equivalent index for the current matrix
+    pub fn coordinate_to_index(&self, Coordinate3(x, y, z): &Coordinate3) -> usize {
+        (z * self.height + y) * self.width + x
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.m.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.m.iter_mut()
+    }
+
+    pub fn set(&mut self, coordinate: &Coordinate3, value: T) {
+        let index = self.coordinate_to_index(coordinate);
+        self.m[index] = value;
+    }
+
+    /// Cross-section at the specified depth: a `width` * `height` matrix
+    pub fn slice_z(&self, z: usize) -> Matrix<T> {
+        let plane = self.width * self.height;
+
+        Matrix {
+            width: self.width,
+            height: self.height,
+            m: self.m[z * plane..(z + 1) * plane].to_vec(),
+        }
+    }
+
+    /// Cross-section at the specified row: a `width` * `depth` matrix
+    pub fn slice_y(&self, y: usize) -> Matrix<T> {
+        Matrix::from_function(self.width, self.depth, |x, z| {
+            *self.get_by_coordinate(&Coordinate3(x, y, z))
+        })
+    }
+
+    /// Cross-section at the specified column: a `height` * `depth` matrix
+    pub fn slice_x(&self, x: usize) -> Matrix<T> {
+        Matrix::from_function(self.height, self.depth, |y, z| {
+            *self.get_by_coordinate(&Coordinate3(x, y, z))
+        })
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_from_constant() {
@@ -178,4 +373,112 @@ mod test {
 
         assert_eq!(result, expected_result);
     }
+
+    #[test]
+    fn test_matrix3() {
+        let matrix = Matrix3::from_function(4, 3, 2, |x, y, z| x + 10 * y + 100 * z);
+
+        assert_eq!(matrix.m.len(), 24);
+        assert_eq!(*matrix.get_by_coordinate(&Coordinate3(3, 2, 1)), 123);
+        matrix.iter().enumerate().for_each(|(index, _)| {
+            let coordinate = matrix.index_to_coordinate(index);
+            assert_eq!(matrix.coordinate_to_index(&coordinate), index);
+        });
+        assert_eq!(matrix.index_to_coordinate(17), Coordinate3(1, 1, 1));
+
+        assert!(Matrix3::from_vec(vec![0; 23], 4, 3, 2).is_err());
+        assert!(Matrix3::from_vec(Vec::<u32>::new(), 0, 3, 2).is_err());
+        assert_eq!(Matrix3::from_vec(matrix.m.clone(), 4, 3, 2), Ok(matrix));
+    }
+
+    #[test]
+    fn test_matrix3_slices() {
+        let matrix = Matrix3::from_function(4, 3, 2, |x, y, z| x + 10 * y + 100 * z);
+
+        assert_eq!(
+            matrix.slice_z(1),
+            Matrix::from_function(4, 3, |x, y| x + 10 * y + 100)
+        );
+        assert_eq!(
+            matrix.slice_y(2),
+            Matrix::from_function(4, 2, |x, z| x + 20 + 100 * z)
+        );
+        assert_eq!(
+            matrix.slice_x(3),
+            Matrix::from_function(3, 2, |y, z| 3 + 10 * y + 100 * z)
+        );
+    }
+
+    #[test]
+    fn test_flips_and_rotations() {
+        // 1 2 3
+        // 4 5 6
+        let matrix = Matrix::from_vec(vec![1, 2, 3, 4, 5, 6], 3, 2).unwrap();
+
+        assert_eq!(matrix.flip_horizontal().m, vec![3, 2, 1, 6, 5, 4]);
+        assert_eq!(matrix.flip_vertical().m, vec![4, 5, 6, 1, 2, 3]);
+
+        let transposed = matrix.transpose();
+        assert_eq!((transposed.width, transposed.height), (2, 3));
+        assert_eq!(transposed.m, vec![1, 4, 2, 5, 3, 6]);
+
+        let rotated = matrix.rotate90();
+        assert_eq!((rotated.width, rotated.height), (2, 3));
+        assert_eq!(rotated.m, vec![4, 1, 5, 2, 6, 3]);
+
+        // A half turn reverses the values
+        let mut reversed = matrix.m.clone();
+        reversed.reverse();
+        assert_eq!(matrix.rotate90().rotate90().m, reversed);
+        assert_eq!(matrix.flip_horizontal().flip_vertical().m, reversed);
+        assert_eq!((0..4).fold(matrix.clone(), |m, _| m.rotate90()), matrix);
+        assert_eq!(matrix.transpose().flip_horizontal(), matrix.rotate90());
+    }
+
+    #[test]
+    fn test_add_scaled_and_scale() {
+        let a = Matrix::from_vec(vec![1, 2, 3, 4], 2, 2).unwrap();
+        let b = Matrix::from_vec(vec![1, 0, -1, 2], 2, 2).unwrap();
+
+        assert_eq!(a.add_scaled(&b, 3).m, vec![4, 2, 0, 10]);
+        assert_eq!(a.scale(-2).m, vec![-2, -4, -6, -8]);
+        assert_eq!((a.scale(2).width, a.scale(2).height), (2, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "Matrix sizes do not match")]
+    fn test_add_scaled_size_mismatch() {
+        let a = Matrix::from_vec(vec![1, 2, 3, 4], 2, 2).unwrap();
+        let b = Matrix::from_vec(vec![1, 0, -1, 2], 4, 1).unwrap();
+
+        a.add_scaled(&b, 3);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_index_coordinate_round_trip(
+            (width, height, index) in (1..100usize, 1..100usize)
+                .prop_flat_map(|(width, height)| (Just(width), Just(height), 0..width * height)),
+        ) {
+            let matrix = Matrix::from_constant(width, height, 0);
+            let coordinate = matrix.index_to_coordinate(index);
+
+            prop_assert!(coordinate.0 < width && coordinate.1 < height);
+            prop_assert_eq!(matrix.coordinate_to_index(&coordinate), index);
+        }
+
+        #[test]
+        fn prop_index_coordinate_3d_round_trip(
+            (width, height, depth, index) in (1..20usize, 1..20usize, 1..20usize)
+                .prop_flat_map(|(width, height, depth)| {
+                    (Just(width), Just(height), Just(depth), 0..width * height * depth)
+                }),
+        ) {
+            let matrix = Matrix3::from_constant(width, height, depth, 0);
+            let coordinate = matrix.index_to_coordinate(index);
+
+            prop_assert!(coordinate.0 < width && coordinate.1 < height && coordinate.2 < depth);
+            prop_assert_eq!(matrix.coordinate_to_index(&coordinate), index);
+        }
+    }
 }
@@ -0,0 +1,109 @@
+use crate::matrix::Matrix;
+
+/// Portable seeded pseudo random number generator
+///
+/// It implements the SplitMix64 algorithm, which only relies on 64 bits
+/// integer arithmetic: the same seed produces the same sequence on every
+/// platform (native or wasm).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Generate the next random 64 bits integer
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generate a random float uniformly distributed in `[0, 1)`
+    pub fn next_f64(&mut self) -> f64 {
+        // Keep the 53 most significant bits, the precision of a f64
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Generate a random boolean that is `true` with the specified probability
+    pub fn next_bool(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    /// Generate a random integer uniformly distributed in `[0, bound)`
+    pub fn next_below(&mut self, bound: usize) -> usize {
+        (self.next_f64() * bound as f64) as usize
+    }
+}
+
+/// Generate a matrix of smooth random values in `[0, 1)`
+///
+/// Random values are drawn on a lattice with a spacing of `scale` cells,
+/// and interpolated between lattice points with a smoothstep function.
+pub fn value_noise(width: usize, height: usize, scale: usize, random: &mut Random) -> Matrix<f64> {
+    let scale = scale.max(1);
+    let lattice_width = width / scale + 2;
+    let lattice_height = height / scale + 2;
+    let lattice = Matrix::from_function(lattice_width, lattice_height, |_, _| random.next_f64());
+
+    let smoothstep = |t: f64| t * t * (3.0 - 2.0 * t);
+    let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+    Matrix::from_function(width, height, |x, y| {
+        let (lattice_x, lattice_y) = (x / scale, y / scale);
+        let tx = smoothstep((x % scale) as f64 / scale as f64);
+        let ty = smoothstep((y % scale) as f64 / scale as f64);
+        let value = |dx: usize, dy: usize| {
+            *lattice.get_by_index((lattice_y + dy) * lattice_width + lattice_x + dx)
+        };
+
+        lerp(
+            lerp(value(0, 0), value(1, 0), tx),
+            lerp(value(0, 1), value(1, 1), tx),
+            ty,
+        )
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_next_u64() {
+        // Reference values of the SplitMix64 algorithm for a null seed
+        let mut random = Random::new(0);
+        assert_eq!(random.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(random.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn test_determinism() {
+        let first: Vec<f64> = {
+            let mut random = Random::new(42);
+            (0..100).map(|_| random.next_f64()).collect()
+        };
+        let second: Vec<f64> = {
+            let mut random = Random::new(42);
+            (0..100).map(|_| random.next_f64()).collect()
+        };
+
+        assert_eq!(first, second);
+        assert!(first.iter().all(|value| (0.0..1.0).contains(value)));
+    }
+
+    #[test]
+    fn test_value_noise() {
+        let noise = value_noise(20, 10, 4, &mut Random::new(7));
+
+        assert_eq!((noise.width, noise.height), (20, 10));
+        assert!(noise.iter().all(|value| (0.0..1.0).contains(value)));
+        assert_eq!(noise, value_noise(20, 10, 4, &mut Random::new(7)));
+    }
+}
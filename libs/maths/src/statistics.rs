@@ -0,0 +1,173 @@
+use std::f64::consts::PI;
+
+use crate::matrix::Matrix;
+
+/// Determine the shortest signed difference to go from `source`
+/// to `destination` along a periodic axis of size `period`
+///
+/// The result is always in `[-period / 2, period / 2]`
+pub fn periodic_difference(source: f64, destination: f64, period: f64) -> f64 {
+    let difference = (destination - source).rem_euclid(period);
+
+    if difference > period / 2.0 {
+        difference - period
+    } else {
+        difference
+    }
+}
+
+/// Calculate the weighted mean of positions along a periodic axis
+///
+/// Each position is mapped to an angle on a circle, the mean angle is then
+/// mapped back to a position in `[0, period)`. This avoids the wrong results
+/// of an arithmetic mean when a distribution crosses the axis edges.
+///
+/// Return `None` if the sum of the weights is null
+pub fn circular_mean<I>(weighted_positions: I, period: f64) -> Option<f64>
+where
+    I: IntoIterator<Item = (f64, f64)>,
+{
+    let (weight_sum, sin_sum, cos_sum) = weighted_positions.into_iter().fold(
+        (0.0, 0.0, 0.0),
+        |(weight_sum, sin_sum, cos_sum), (position, weight)| {
+            let angle = 2.0 * PI * position / period;
+            (
+                weight_sum + weight,
+                sin_sum + weight * angle.sin(),
+                cos_sum + weight * angle.cos(),
+            )
+        },
+    );
+
+    if weight_sum <= 0.0 {
+        return None;
+    }
+
+    let mean_angle = sin_sum.atan2(cos_sum).rem_euclid(2.0 * PI);
+
+    Some((mean_angle * period / (2.0 * PI)) % period)
+}
+
+/// Calculate the center of mass of a matrix on a torus
+///
+/// The matrix values are used as weights. Return `None` if
+/// the matrix has no mass.
+pub fn toroidal_centroid(matrix: &Matrix<f64>) -> Option<(f64, f64)> {
+    let mut column_masses = vec![0.0; matrix.width];
+    let mut row_masses = vec![0.0; matrix.height];
+
+    matrix.iter().enumerate().for_each(|(index, value)| {
+        column_masses[index % matrix.width] += value;
+        row_masses[index / matrix.width] += value;
+    });
+
+    let x = circular_mean(
+        column_masses
+            .into_iter()
+            .enumerate()
+            .map(|(x, mass)| (x as f64, mass)),
+        matrix.width as f64,
+    )?;
+    let y = circular_mean(
+        row_masses
+            .into_iter()
+            .enumerate()
+            .map(|(y, mass)| (y as f64, mass)),
+        matrix.height as f64,
+    )?;
+
+    Some((x, y))
+}
+
+/// Calculate the mass weighted variance of the squared
+/// toroidal distances between each cell and a center point
+///
+/// Return 0 if the matrix has no mass.
+pub fn toroidal_variance(matrix: &Matrix<f64>, center: (f64, f64)) -> f64 {
+    let (mass, weighted_sum) =
+        matrix
+            .iter()
+            .enumerate()
+            .fold((0.0, 0.0), |(mass, weighted_sum), (index, value)| {
+                let x = (index % matrix.width) as f64;
+                let y = (index / matrix.width) as f64;
+                let dx = periodic_difference(center.0, x, matrix.width as f64);
+                let dy = periodic_difference(center.1, y, matrix.height as f64);
+
+                (mass + value, weighted_sum + value * (dx * dx + dy * dy))
+            });
+
+    if mass <= 0.0 {
+        0.0
+    } else {
+        weighted_sum / mass
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_periodic_difference() {
+        let data = [
+            (0.0, 1.0, 1.0),
+            (1.0, 0.0, -1.0),
+            (0.0, 9.0, -1.0),
+            (9.0, 0.0, 1.0),
+            (2.0, 2.0, 0.0),
+            (0.0, 5.0, 5.0),
+        ];
+
+        data.iter().for_each(|(source, destination, result)| {
+            assert_eq!(periodic_difference(*source, *destination, 10.0), *result)
+        });
+    }
+
+    #[test]
+    fn test_toroidal_centroid() {
+        // Empty matrix has no centroid
+        assert_eq!(toroidal_centroid(&Matrix::from_constant(4, 4, 0.0)), None);
+
+        // Mass in the middle of the matrix
+        let matrix = Matrix::from_function(10, 10, |x, y| {
+            if (4..=6).contains(&x) && y == 2 {
+                1.0
+            } else {
+                0.0
+            }
+        });
+        let (x, y) = toroidal_centroid(&matrix).unwrap();
+        assert!((x - 5.0).abs() < 1e-9);
+        assert!((y - 2.0).abs() < 1e-9);
+
+        // Mass split across the matrix edges
+        let matrix = Matrix::from_function(10, 10, |x, y| {
+            if (x == 9 || x == 0 || x == 1) && y == 0 {
+                1.0
+            } else {
+                0.0
+            }
+        });
+        let (x, y) = toroidal_centroid(&matrix).unwrap();
+        assert!(x.abs() < 1e-9 || (x - 10.0).abs() < 1e-9);
+        assert!(y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_toroidal_variance() {
+        let matrix = Matrix::from_function(10, 10, |x, y| {
+            if (x == 9 || x == 1) && y == 0 {
+                1.0
+            } else {
+                0.0
+            }
+        });
+
+        assert!((toroidal_variance(&matrix, (0.0, 0.0)) - 1.0).abs() < 1e-9);
+        assert_eq!(
+            toroidal_variance(&Matrix::from_constant(4, 4, 0.0), (0.0, 0.0)),
+            0.0
+        );
+    }
+}
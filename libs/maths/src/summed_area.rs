@@ -0,0 +1,91 @@
+/// Summed-area table of a toroidal (periodic) grid
+///
+/// The grid is padded with its own wrapped values, so that the sum of any
+/// rectangle reaching at most `padding` cells outside of the grid is
+/// computed in constant time, whatever its size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummedAreaTable {
+    width: usize,
+    height: usize,
+    padding: usize,
+    table: Vec<u32>,
+}
+
+impl SummedAreaTable {
+    /// Build the table of a `width` * `height` grid, stored row by row
+    /// in `values`
+    pub fn toroidal(values: &[u32], width: usize, height: usize, padding: usize) -> Self {
+        let table_width = width + 2 * padding + 1;
+        let table_height = height + 2 * padding + 1;
+        let mut table = vec![0; table_width * table_height];
+
+        // The first row and column of the table stay at 0, so that
+        // rectangles touching the padding edges do not need special cases
+        for table_y in 1..table_height {
+            let y = (table_y - 1 + height * (padding / height + 1) - padding) % height;
+            let mut row_sum = 0;
+
+            for table_x in 1..table_width {
+                let x = (table_x - 1 + width * (padding / width + 1) - padding) % width;
+                row_sum += values[y * width + x];
+                table[table_y * table_width + table_x] =
+                    table[(table_y - 1) * table_width + table_x] + row_sum;
+            }
+        }
+
+        Self {
+            width,
+            height,
+            padding,
+            table,
+        }
+    }
+
+    /// Sum the values of the rectangle going from (`x_min`, `y_min`)
+    /// to (`x_max`, `y_max`), bounds included
+    ///
+    /// Coordinates can go up to `padding` cells outside of the grid,
+    /// they are then wrapped around the grid edges.
+    pub fn sum(&self, x_min: isize, y_min: isize, x_max: isize, y_max: isize) -> u32 {
+        let table_width = self.width + 2 * self.padding + 1;
+        let padding = self.padding as isize;
+        let index =
+            |x: isize, y: isize| (y + padding) as usize * table_width + (x + padding) as usize;
+
+        self.table[index(x_max + 1, y_max + 1)] + self.table[index(x_min, y_min)]
+            - self.table[index(x_min, y_max + 1)]
+            - self.table[index(x_max + 1, y_min)]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sum() {
+        // 1 2 3
+        // 4 5 6
+        let values = [1, 2, 3, 4, 5, 6];
+        let table = SummedAreaTable::toroidal(&values, 3, 2, 2);
+
+        assert_eq!(table.sum(0, 0, 2, 1), 21);
+        assert_eq!(table.sum(1, 1, 1, 1), 5);
+        assert_eq!(table.sum(0, 0, 1, 0), 3);
+        // Wrapped around the left edge: 3 1
+        assert_eq!(table.sum(-1, 0, 0, 0), 4);
+        // Wrapped around the bottom edge: 4 then 1
+        assert_eq!(table.sum(0, 1, 0, 2), 5);
+        // Wrapped on both axes, the whole grid and its middle column again
+        assert_eq!(table.sum(-2, -2, 1, -1), 21 + 2 + 5);
+    }
+
+    #[test]
+    fn test_padding_larger_than_grid() {
+        let values = [1, 2];
+        let table = SummedAreaTable::toroidal(&values, 2, 1, 3);
+
+        assert_eq!(table.sum(-3, 0, 3, 0), 2 + 1 + 2 + 1 + 2 + 1 + 2);
+        assert_eq!(table.sum(0, -3, 0, 3), 7);
+    }
+}
@@ -0,0 +1,338 @@
+use std::sync::{Arc, Mutex};
+
+/// Memory shared between a worker and the main thread
+///
+/// It is addressed in bytes, its first bytes also being read and
+/// written as 32 bits words, atomically, to synchronise both sides.
+pub trait SharedMemory {
+    /// Size of the memory, in bytes
+    fn byte_length(&self) -> usize;
+
+    /// Atomically read the word at the specified index
+    fn load(&self, word: usize) -> u32;
+
+    /// Atomically write the word at the specified index
+    fn store(&self, word: usize, value: u32);
+
+    /// Copy the bytes starting at `offset` into `destination`
+    fn read(&self, offset: usize, destination: &mut [u8]);
+
+    /// Copy `source` to the bytes starting at `offset`
+    fn write(&self, offset: usize, source: &[u8]);
+}
+
+/// Word incremented before and after each publication:
+/// odd while a frame is being written
+const SEQUENCE: usize = 0;
+/// Word holding the index (0 or 1) of the last frame published
+const FRONT: usize = 1;
+/// Words holding the generation and the length (in bytes) of each frame
+const FRAME_HEADERS: usize = 2;
+/// Size of the header, keeping the frames aligned for 64 bits values
+const HEADER_BYTES: usize = 24;
+
+/// Two frames in a shared memory: the worker writes a state in the back
+/// frame, while the main thread reads the last one published
+///
+/// The frames are guarded by a sequence lock: publishing a frame makes
+/// the sequence odd, writes the frame, swaps the frames, then makes the
+/// sequence even again. A reader only keeps a frame copied while the
+/// sequence was even and unchanged, and starts again otherwise.
+pub struct DoubleBuffer {
+    memory: Box<dyn SharedMemory>,
+    frame_capacity: usize,
+}
+
+impl DoubleBuffer {
+    /// Size (in bytes) of a shared memory holding frames of
+    /// at most the specified size (in bytes)
+    pub fn required_bytes(frame_capacity: usize) -> usize {
+        HEADER_BYTES + 2 * frame_capacity.next_multiple_of(8)
+    }
+
+    /// Use a shared memory as a double buffer
+    ///
+    /// Both sides of the memory must use the same layout: the memory is
+    /// expected to be zeroed before any of them starts using it.
+    pub fn new(memory: Box<dyn SharedMemory>) -> Result<Self, String> {
+        let byte_length = memory.byte_length();
+        if byte_length < Self::required_bytes(1) {
+            return Err(format!("Shared memory is too small: {} bytes", byte_length));
+        }
+
+        Ok(Self {
+            frame_capacity: (byte_length - HEADER_BYTES) / 2 / 8 * 8,
+            memory,
+        })
+    }
+
+    /// Largest frame (in bytes) the buffer can hold
+    pub fn frame_capacity(&self) -> usize {
+        self.frame_capacity
+    }
+
+    /// Number of frames published so far
+    pub fn sequence(&self) -> u32 {
+        self.memory.load(SEQUENCE) / 2
+    }
+
+    /// Write a frame in the back buffer, then make it the front one
+    pub fn publish(&self, generation: u32, frame: &[u8]) -> Result<(), String> {
+        if frame.len() > self.frame_capacity {
+            return Err(format!(
+                "Frame of {} bytes does not fit in the shared memory",
+                frame.len()
+            ));
+        }
+
+        let sequence = self.memory.load(SEQUENCE);
+        self.memory.store(SEQUENCE, sequence.wrapping_add(1));
+
+        let back = 1 - self.memory.load(FRONT) as usize;
+        self.memory.write(self.frame_offset(back), frame);
+        self.memory.store(FRAME_HEADERS + 2 * back, generation);
+        self.memory
+            .store(FRAME_HEADERS + 2 * back + 1, frame.len() as u32);
+
+        self.memory.store(FRONT, back as u32);
+        self.memory.store(SEQUENCE, sequence.wrapping_add(2));
+
+        Ok(())
+    }
+
+    /// Copy the last frame published, with its generation
+    ///
+    /// Return `None` if no frame was published yet.
+    pub fn read(&self) -> Option<(u32, Vec<u8>)> {
+        loop {
+            let sequence = self.memory.load(SEQUENCE);
+            if sequence == 0 {
+                return None;
+            }
+
+            // A frame is being written
+            if sequence % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+
+            let front = self.memory.load(FRONT) as usize;
+            let generation = self.memory.load(FRAME_HEADERS + 2 * front);
+            let length = self.memory.load(FRAME_HEADERS + 2 * front + 1) as usize;
+            let mut frame = vec![0; length.min(self.frame_capacity)];
+            self.memory.read(self.frame_offset(front), &mut frame);
+
+            // Nothing was written while the frame was being copied
+            if self.memory.load(SEQUENCE) == sequence {
+                return Some((generation, frame));
+            }
+        }
+    }
+
+    fn frame_offset(&self, frame: usize) -> usize {
+        HEADER_BYTES + frame * self.frame_capacity
+    }
+}
+
+/// Shared memory of a native process: a stand-in for a
+/// `SharedArrayBuffer`, shared between threads by cloning it
+#[derive(Debug, Clone)]
+pub struct InProcessMemory {
+    bytes: Arc<Mutex<Vec<u8>>>,
+}
+
+impl InProcessMemory {
+    /// Allocate a zeroed memory of the specified size (in bytes)
+    pub fn new(byte_length: usize) -> Self {
+        Self {
+            bytes: Arc::new(Mutex::new(vec![0; byte_length])),
+        }
+    }
+}
+
+impl SharedMemory for InProcessMemory {
+    fn byte_length(&self) -> usize {
+        self.bytes.lock().unwrap().len()
+    }
+
+    fn load(&self, word: usize) -> u32 {
+        let bytes = self.bytes.lock().unwrap();
+        u32::from_le_bytes(bytes[4 * word..4 * word + 4].try_into().unwrap())
+    }
+
+    fn store(&self, word: usize, value: u32) {
+        let mut bytes = self.bytes.lock().unwrap();
+        bytes[4 * word..4 * word + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn read(&self, offset: usize, destination: &mut [u8]) {
+        let bytes = self.bytes.lock().unwrap();
+        destination.copy_from_slice(&bytes[offset..offset + destination.len()]);
+    }
+
+    fn write(&self, offset: usize, source: &[u8]) {
+        let mut bytes = self.bytes.lock().unwrap();
+        bytes[offset..offset + source.len()].copy_from_slice(source);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::mpsc::channel;
+
+    /// Memory running hooks in the middle of its operations,
+    /// to interleave a reader and a writer deterministically
+    struct Hooked {
+        memory: InProcessMemory,
+        /// Run between the two halves of a copy out of the memory
+        on_read: Box<dyn Fn() + Send>,
+        /// Run after a copy into the memory
+        on_write: Box<dyn Fn() + Send>,
+        /// Run before a word is loaded
+        on_load: Box<dyn Fn(usize) + Send>,
+    }
+
+    impl Hooked {
+        fn new(memory: InProcessMemory) -> Self {
+            Self {
+                memory,
+                on_read: Box::new(|| {}),
+                on_write: Box::new(|| {}),
+                on_load: Box::new(|_| {}),
+            }
+        }
+    }
+
+    impl SharedMemory for Hooked {
+        fn byte_length(&self) -> usize {
+            self.memory.byte_length()
+        }
+
+        fn load(&self, word: usize) -> u32 {
+            (self.on_load)(word);
+            self.memory.load(word)
+        }
+
+        fn store(&self, word: usize, value: u32) {
+            self.memory.store(word, value)
+        }
+
+        fn read(&self, offset: usize, destination: &mut [u8]) {
+            let half = destination.len() / 2;
+            self.memory.read(offset, &mut destination[..half]);
+            (self.on_read)();
+            self.memory.read(offset + half, &mut destination[half..]);
+        }
+
+        fn write(&self, offset: usize, source: &[u8]) {
+            self.memory.write(offset, source);
+            (self.on_write)();
+        }
+    }
+
+    #[test]
+    fn test_double_buffer() {
+        let memory = InProcessMemory::new(DoubleBuffer::required_bytes(10));
+        let writer = DoubleBuffer::new(Box::new(memory.clone())).unwrap();
+        let reader = DoubleBuffer::new(Box::new(memory)).unwrap();
+
+        assert_eq!(writer.frame_capacity(), 16);
+        assert_eq!(reader.read(), None);
+
+        writer.publish(1, &[1, 2, 3]).unwrap();
+        assert_eq!(reader.read(), Some((1, vec![1, 2, 3])));
+        writer.publish(2, &[4; 10]).unwrap();
+        writer.publish(3, &[5, 6]).unwrap();
+        assert_eq!(reader.read(), Some((3, vec![5, 6])));
+        assert_eq!(reader.sequence(), 3);
+
+        assert!(writer.publish(4, &[0; 17]).is_err());
+        assert!(DoubleBuffer::new(Box::new(InProcessMemory::new(24))).is_err());
+    }
+
+    #[test]
+    fn test_concurrent_reads() {
+        let memory = InProcessMemory::new(DoubleBuffer::required_bytes(64));
+        let writer_memory = memory.clone();
+
+        // Frames are filled with their generation: a torn read would mix them
+        let writer = std::thread::spawn(move || {
+            let buffer = DoubleBuffer::new(Box::new(writer_memory)).unwrap();
+            (1..=500u32).for_each(|generation| {
+                buffer.publish(generation, &[generation as u8; 64]).unwrap()
+            });
+        });
+
+        let reader = DoubleBuffer::new(Box::new(memory)).unwrap();
+        let mut last_generation = 0;
+        while last_generation < 500 {
+            if let Some((generation, frame)) = reader.read() {
+                assert!(generation >= last_generation);
+                assert!(frame.iter().all(|&byte| byte == generation as u8));
+                last_generation = generation;
+            }
+        }
+
+        writer.join().unwrap();
+    }
+
+    #[test]
+    fn test_publish_while_reading() {
+        let memory = InProcessMemory::new(DoubleBuffer::required_bytes(64));
+        DoubleBuffer::new(Box::new(memory.clone()))
+            .unwrap()
+            .publish(1, &[1; 64])
+            .unwrap();
+
+        let (go, wait_go) = channel();
+        let (paused, wait_paused) = channel();
+        let (resume, wait_resume) = channel();
+
+        // While the reader copies frame 1, the writer publishes frame 2,
+        // then stops in the middle of publishing frame 3, which
+        // overwrites the frame being copied
+        let mut writer_memory = Hooked::new(memory.clone());
+        let writes = AtomicUsize::new(0);
+        writer_memory.on_write = Box::new(move || {
+            if writes.fetch_add(1, Ordering::SeqCst) == 1 {
+                paused.send(()).unwrap();
+                wait_resume.recv().unwrap();
+            }
+        });
+        let writer = std::thread::spawn(move || {
+            let buffer = DoubleBuffer::new(Box::new(writer_memory)).unwrap();
+            wait_go.recv().unwrap();
+            buffer.publish(2, &[2; 64]).unwrap();
+            buffer.publish(3, &[3; 64]).unwrap();
+        });
+
+        let torn = std::sync::Arc::new(AtomicUsize::new(0));
+        let mut reader_memory = Hooked::new(memory);
+        let copies = torn.clone();
+        reader_memory.on_read = Box::new(move || {
+            if copies.fetch_add(1, Ordering::SeqCst) == 0 {
+                go.send(()).unwrap();
+                wait_paused.recv().unwrap();
+            }
+        });
+        // The writer only finishes once the reader checked the
+        // sequence twice after the interrupted copy
+        let checks = AtomicUsize::new(0);
+        reader_memory.on_load = Box::new(move |word| {
+            if word == SEQUENCE
+                && torn.load(Ordering::SeqCst) > 0
+                && checks.fetch_add(1, Ordering::SeqCst) == 1
+            {
+                resume.send(()).unwrap();
+            }
+        });
+
+        let reader = DoubleBuffer::new(Box::new(reader_memory)).unwrap();
+        assert_eq!(reader.read(), Some((3, vec![3; 64])));
+        assert_eq!(reader.sequence(), 3);
+
+        writer.join().unwrap();
+    }
+}
@@ -0,0 +1,151 @@
+/// Binary writer of the messages exchanged with a worker
+///
+/// Values are written in little endian, so that messages are the same
+/// on every platform. Messages are plain bytes: they can be transferred
+/// to a worker with `postMessage` without being copied.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn u8(mut self, value: u8) -> Self {
+        self.bytes.push(value);
+        self
+    }
+
+    pub fn u32(mut self, value: u32) -> Self {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    pub fn f64(mut self, value: f64) -> Self {
+        self.bytes.extend_from_slice(&value.to_le_bytes());
+        self
+    }
+
+    /// Write a sequence of bytes, preceded by its length
+    pub fn bytes(self, value: &[u8]) -> Self {
+        let mut encoder = self.u32(value.len() as u32);
+        encoder.bytes.extend_from_slice(value);
+        encoder
+    }
+
+    /// Write a sequence of floats, preceded by its length
+    pub fn f64s(self, values: &[f64]) -> Self {
+        values
+            .iter()
+            .fold(self.u32(values.len() as u32), |encoder, &value| {
+                encoder.f64(value)
+            })
+    }
+
+    pub fn string(self, value: &str) -> Self {
+        self.bytes(value.as_bytes())
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Binary reader of the messages written by an `Encoder`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub fn f64(&mut self) -> Result<f64, String> {
+        Ok(f64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    pub fn bytes(&mut self) -> Result<Vec<u8>, String> {
+        let length = self.u32()? as usize;
+        Ok(self.take(length)?.to_vec())
+    }
+
+    pub fn f64s(&mut self) -> Result<Vec<f64>, String> {
+        let length = self.u32()? as usize;
+        (0..length).map(|_| self.f64()).collect()
+    }
+
+    pub fn string(&mut self) -> Result<String, String> {
+        String::from_utf8(self.bytes()?).map_err(|_| "Invalid string in message".to_string())
+    }
+
+    /// Fail if some bytes were not read: the message is not the expected one
+    pub fn finish(self) -> Result<(), String> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("{} unexpected bytes in message", self.bytes.len()))
+        }
+    }
+
+    fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < length {
+            return Err("Truncated message".to_string());
+        }
+
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let message = Encoder::new()
+            .u8(3)
+            .u32(70_000)
+            .f64(-0.25)
+            .bytes(&[1, 2, 3])
+            .f64s(&[0.5, 1.5])
+            .string("B3/S23")
+            .finish();
+
+        let mut decoder = Decoder::new(&message);
+        assert_eq!(decoder.u8(), Ok(3));
+        assert_eq!(decoder.u32(), Ok(70_000));
+        assert_eq!(decoder.f64(), Ok(-0.25));
+        assert_eq!(decoder.bytes(), Ok(vec![1, 2, 3]));
+        assert_eq!(decoder.f64s(), Ok(vec![0.5, 1.5]));
+        assert_eq!(decoder.string(), Ok("B3/S23".to_string()));
+        assert_eq!(decoder.finish(), Ok(()));
+    }
+
+    #[test]
+    fn test_invalid_messages() {
+        assert!(Decoder::new(&[1, 2]).u32().is_err());
+        // Length larger than the message
+        assert!(Decoder::new(&Encoder::new().u32(10).u8(1).finish())
+            .bytes()
+            .is_err());
+
+        let message = Encoder::new().u32(1).u8(0).finish();
+        let mut decoder = Decoder::new(&message);
+        decoder.u32().unwrap();
+        assert!(decoder.finish().is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    sync::mpsc::{channel, Receiver, Sender},
+    thread::JoinHandle,
+};
+
+/// Side of a worker answering the messages of the main thread
+pub trait Handler {
+    /// Handle an encoded command, and return the encoded response
+    fn handle(&mut self, message: &[u8]) -> Vec<u8>;
+}
+
+/// Native stand-in for a Web Worker: a thread running a handler,
+/// exchanging messages through channels as `postMessage` would
+pub struct InProcessWorker {
+    sender: Option<Sender<Vec<u8>>>,
+    receiver: Receiver<Vec<u8>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InProcessWorker {
+    /// Start a worker thread, building its handler in the thread
+    ///
+    /// The handler itself does not need to be `Send`, as the state of a
+    /// Web Worker never leaves it.
+    pub fn spawn<H, F>(factory: F) -> Self
+    where
+        H: Handler,
+        F: FnOnce() -> H + Send + 'static,
+    {
+        let (sender, commands) = channel::<Vec<u8>>();
+        let (responses, receiver) = channel();
+
+        let thread = std::thread::spawn(move || {
+            let mut handler = factory();
+            for message in commands {
+                if responses.send(handler.handle(&message)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            sender: Some(sender),
+            receiver,
+            thread: Some(thread),
+        }
+    }
+
+    /// Send a message to the worker, without waiting for its response
+    pub fn post(&self, message: Vec<u8>) {
+        if let Some(sender) = &self.sender {
+            sender.send(message).expect("Worker thread stopped");
+        }
+    }
+
+    /// Wait for the next response of the worker
+    pub fn receive(&self) -> Vec<u8> {
+        self.receiver.recv().expect("Worker thread stopped")
+    }
+
+    /// Send a message to the worker and wait for its response
+    pub fn request(&self, message: Vec<u8>) -> Vec<u8> {
+        self.post(message);
+        self.receive()
+    }
+}
+
+impl Drop for InProcessWorker {
+    fn drop(&mut self) {
+        // Closing the channel stops the worker loop
+        self.sender.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Counter(u8);
+
+    impl Handler for Counter {
+        fn handle(&mut self, message: &[u8]) -> Vec<u8> {
+            self.0 += message.len() as u8;
+            vec![self.0]
+        }
+    }
+
+    #[test]
+    fn test_messages() {
+        let worker = InProcessWorker::spawn(|| Counter(0));
+
+        assert_eq!(worker.request(vec![1, 2]), vec![2]);
+
+        // Responses come back in the order of the messages
+        worker.post(vec![0]);
+        worker.post(vec![0, 0, 0]);
+        assert_eq!(worker.receive(), vec![3]);
+        assert_eq!(worker.receive(), vec![6]);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod buffer;
+pub mod codec;
+pub mod in_process;
+pub mod protocol;
+pub mod shared_array;
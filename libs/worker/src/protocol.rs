@@ -0,0 +1,251 @@
+use crate::{
+    buffer::{DoubleBuffer, SharedMemory},
+    codec::{Decoder, Encoder},
+    in_process::Handler,
+};
+
+/// Simulation driven by the commands of the main thread
+///
+/// Its state is sent to the main thread as a frame of bytes, either in
+/// the responses or through a shared buffer.
+pub trait Simulation {
+    /// Apply an encoded command
+    fn apply(&mut self, message: &[u8]) -> Result<(), String>;
+
+    /// Generation (or step) of the current state
+    fn generation(&self) -> u32;
+
+    /// Current state, encoded as a frame
+    fn frame(&self) -> Vec<u8>;
+}
+
+/// Responses sent by the worker after each command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// State of the simulation, encoded by `Simulation::frame`
+    Snapshot {
+        generation: u32,
+        frame: Vec<u8>,
+    },
+    /// The state was published in the shared buffer
+    Published {
+        generation: u32,
+    },
+    Error(String),
+}
+
+impl Response {
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Response::Snapshot { generation, frame } => {
+                Encoder::new().u8(0).u32(*generation).bytes(frame)
+            }
+            Response::Published { generation } => Encoder::new().u8(1).u32(*generation),
+            Response::Error(error) => Encoder::new().u8(2).string(error),
+        }
+        .finish()
+    }
+
+    pub fn decode(message: &[u8]) -> Result<Self, String> {
+        let mut decoder = Decoder::new(message);
+        let response = match decoder.u8()? {
+            0 => Response::Snapshot {
+                generation: decoder.u32()?,
+                frame: decoder.bytes()?,
+            },
+            1 => Response::Published {
+                generation: decoder.u32()?,
+            },
+            2 => Response::Error(decoder.string()?),
+            tag => return Err(format!("Unknown response: {}", tag)),
+        };
+        decoder.finish()?;
+
+        Ok(response)
+    }
+}
+
+/// Worker side: applies the commands to a simulation, then sends its
+/// state back in the response, or publishes it in a shared buffer when
+/// one is attached, to avoid copying it through messages
+pub struct Worker<S: Simulation> {
+    simulation: S,
+    buffer: Option<DoubleBuffer>,
+}
+
+impl<S: Simulation> Worker<S> {
+    pub fn new(simulation: S) -> Self {
+        Self {
+            simulation,
+            buffer: None,
+        }
+    }
+
+    /// Publish the next states in a shared memory
+    pub fn attach_memory(&mut self, memory: Box<dyn SharedMemory>) -> Result<(), String> {
+        self.buffer = Some(DoubleBuffer::new(memory)?);
+        Ok(())
+    }
+
+    fn apply(&mut self, message: &[u8]) -> Result<Response, String> {
+        self.simulation.apply(message)?;
+
+        let generation = self.simulation.generation();
+        let frame = self.simulation.frame();
+
+        match &self.buffer {
+            Some(buffer) => {
+                buffer.publish(generation, &frame)?;
+                Ok(Response::Published { generation })
+            }
+            None => Ok(Response::Snapshot { generation, frame }),
+        }
+    }
+}
+
+impl<S: Simulation> Handler for Worker<S> {
+    /// Apply an encoded command, and return the encoded response
+    fn handle(&mut self, message: &[u8]) -> Vec<u8> {
+        self.apply(message).unwrap_or_else(Response::Error).encode()
+    }
+}
+
+/// Main thread side of a `Worker`: keeps the last state received,
+/// from the responses or from the shared buffer
+pub struct Client {
+    generation: u32,
+    frame: Vec<u8>,
+    buffer: Option<DoubleBuffer>,
+}
+
+impl Client {
+    /// Client holding the specified frame until a state is received
+    pub fn new(frame: Vec<u8>) -> Self {
+        Self {
+            generation: 0,
+            frame,
+            buffer: None,
+        }
+    }
+
+    /// Read the states published by the worker in a shared memory
+    pub fn attach_memory(&mut self, memory: Box<dyn SharedMemory>) -> Result<(), String> {
+        self.buffer = Some(DoubleBuffer::new(memory)?);
+        Ok(())
+    }
+
+    /// Read a response of the worker, updating the generation and the frame
+    pub fn receive(&mut self, message: &[u8]) -> Result<(), String> {
+        match Response::decode(message)? {
+            Response::Snapshot { generation, frame } => {
+                self.generation = generation;
+                self.frame = frame;
+            }
+            Response::Published { .. } => {
+                // A later state may already be published: read the latest one
+                if let Some((generation, frame)) =
+                    self.buffer.as_ref().and_then(|buffer| buffer.read())
+                {
+                    self.generation = generation;
+                    self.frame = frame;
+                }
+            }
+            Response::Error(error) => return Err(error),
+        }
+
+        Ok(())
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Last state received, encoded by `Simulation::frame`
+    pub fn frame(&self) -> &[u8] {
+        &self.frame
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{buffer::InProcessMemory, in_process::InProcessWorker};
+
+    /// Adds the byte of each command to its state
+    struct Counter(u8);
+
+    impl Simulation for Counter {
+        fn apply(&mut self, message: &[u8]) -> Result<(), String> {
+            match message {
+                [value] => {
+                    self.0 += value;
+                    Ok(())
+                }
+                _ => Err("Invalid command".to_string()),
+            }
+        }
+
+        fn generation(&self) -> u32 {
+            self.0 as u32
+        }
+
+        fn frame(&self) -> Vec<u8> {
+            vec![self.0; 3]
+        }
+    }
+
+    #[test]
+    fn test_responses() {
+        [
+            Response::Snapshot {
+                generation: 4,
+                frame: vec![1, 2, 3],
+            },
+            Response::Published { generation: 7 },
+            Response::Error("Invalid rule".to_string()),
+        ]
+        .into_iter()
+        .for_each(|response| assert_eq!(Response::decode(&response.encode()), Ok(response)));
+
+        assert!(Response::decode(&[3]).is_err());
+    }
+
+    #[test]
+    fn test_snapshots() {
+        let worker = InProcessWorker::spawn(|| Worker::new(Counter(0)));
+        let mut client = Client::new(vec![0; 3]);
+
+        client.receive(&worker.request(vec![2])).unwrap();
+        assert_eq!(client.generation(), 2);
+        assert_eq!(client.frame(), &[2, 2, 2]);
+
+        // Errors of the simulation are reported to the client
+        assert_eq!(
+            client.receive(&worker.request(vec![1, 1])),
+            Err("Invalid command".to_string())
+        );
+        assert_eq!(client.frame(), &[2, 2, 2]);
+    }
+
+    #[test]
+    fn test_shared_buffer() {
+        let memory = InProcessMemory::new(DoubleBuffer::required_bytes(3));
+        let worker_memory = memory.clone();
+        let worker = InProcessWorker::spawn(move || {
+            let mut worker = Worker::new(Counter(0));
+            worker.attach_memory(Box::new(worker_memory)).unwrap();
+            worker
+        });
+        let mut client = Client::new(vec![0; 3]);
+        client.attach_memory(Box::new(memory)).unwrap();
+
+        let response = worker.request(vec![5]);
+        assert_eq!(
+            Response::decode(&response),
+            Ok(Response::Published { generation: 5 })
+        );
+        client.receive(&response).unwrap();
+        assert_eq!(client.generation(), 5);
+        assert_eq!(client.frame(), &[5, 5, 5]);
+    }
+}
@@ -0,0 +1,45 @@
+use js_sys::{Atomics, Int32Array, SharedArrayBuffer, Uint8Array};
+
+use crate::buffer::SharedMemory;
+
+/// Shared memory backed by a `SharedArrayBuffer`, available in
+/// cross-origin isolated pages
+pub struct SharedArrayMemory {
+    bytes: Uint8Array,
+    words: Int32Array,
+}
+
+impl SharedArrayMemory {
+    pub fn new(buffer: &SharedArrayBuffer) -> Self {
+        Self {
+            bytes: Uint8Array::new(buffer),
+            words: Int32Array::new(buffer),
+        }
+    }
+}
+
+impl SharedMemory for SharedArrayMemory {
+    fn byte_length(&self) -> usize {
+        self.bytes.length() as usize
+    }
+
+    fn load(&self, word: usize) -> u32 {
+        Atomics::load(&self.words, word as u32).expect("Invalid shared memory") as u32
+    }
+
+    fn store(&self, word: usize, value: u32) {
+        Atomics::store(&self.words, word as u32, value as i32).expect("Invalid shared memory");
+    }
+
+    fn read(&self, offset: usize, destination: &mut [u8]) {
+        self.bytes
+            .subarray(offset as u32, (offset + destination.len()) as u32)
+            .copy_to(destination);
+    }
+
+    fn write(&self, offset: usize, source: &[u8]) {
+        self.bytes
+            .subarray(offset as u32, (offset + source.len()) as u32)
+            .copy_from(source);
+    }
+}